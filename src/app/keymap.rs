@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use crossterm::event::KeyCode;
+
+/// Actions which can be bound to keys
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyAction {
+    Quit,
+    Help,
+    Reload,
+    SortName,
+    SortStat,
+    SortPid,
+    PrevStat,
+    NextStat,
+    ChooseStat,
+    Export,
+    ExportJson,
+    CollapseAll,
+    Split,
+    ShowProcs,
+    ShowThreads,
+    ShowHierProcs,
+    ShowHierThreads,
+    ToggleOomGroup,
+    ToggleTaskCounts,
+    ToggleProcAverage,
+    ToggleInode,
+    ToggleSubtreeControl,
+    TogglePopulatedOnly,
+    ToggleHideEmpty,
+    ToggleLegend,
+    ToggleGrowth,
+    ToggleUnescape,
+    ToggleReclaim,
+    ToggleState,
+    ToggleIoRates,
+    ToggleThreads,
+    ToggleChildren,
+    IncreaseLimit,
+    DecreaseLimit,
+    ToggleWatch,
+    ToggleComm,
+    ToggleContainer,
+    ToggleHistogram,
+    ToggleUserColours,
+    ToggleProcTree,
+    ProcDetail,
+    EventsDetail,
+    FlatView,
+    Footprint,
+    Search,
+    Filter,
+    StateFilter,
+    WatchCGroup,
+    GraphCGroup,
+    TogglePause,
+    Step,
+    ToggleUnits,
+    ToggleRawBytes,
+    MarkReference,
+    Terminate,
+    Kill,
+    Freeze,
+    EditNote,
+    CopyPath,
+    JumpOwnCGroup,
+}
+
+/// Action names used in the configuration file and default keys
+const BINDINGS: [(KeyAction, &str, char); 60] = [
+    (KeyAction::Quit, "quit", 'q'),
+    (KeyAction::Help, "help", 'h'),
+    (KeyAction::Reload, "reload", 'r'),
+    (KeyAction::SortName, "sort-name", 'n'),
+    (KeyAction::SortStat, "sort-stat", 's'),
+    (KeyAction::SortPid, "sort-pid", 'i'),
+    (KeyAction::PrevStat, "prev-stat", '['),
+    (KeyAction::NextStat, "next-stat", ']'),
+    (KeyAction::ChooseStat, "choose-stat", 'z'),
+    (KeyAction::Export, "export", 'E'),
+    (KeyAction::ExportJson, "export-json", 'e'),
+    (KeyAction::CollapseAll, "collapse-all", 'c'),
+    (KeyAction::Split, "split", 'f'),
+    (KeyAction::ShowProcs, "show-procs", 'p'),
+    (KeyAction::ShowThreads, "show-threads", 't'),
+    (KeyAction::ShowHierProcs, "show-hier-procs", 'P'),
+    (KeyAction::ShowHierThreads, "show-hier-threads", 'T'),
+    (KeyAction::ToggleOomGroup, "toggle-oom-group", 'o'),
+    (KeyAction::ToggleTaskCounts, "toggle-task-counts", 'x'),
+    (KeyAction::ToggleProcAverage, "toggle-proc-average", 'X'),
+    (KeyAction::ToggleInode, "toggle-inode", 'i'),
+    (
+        KeyAction::ToggleSubtreeControl,
+        "toggle-subtree-control",
+        'D',
+    ),
+    (KeyAction::TogglePopulatedOnly, "toggle-populated-only", 'a'),
+    (KeyAction::ToggleHideEmpty, "toggle-hide-empty", 'H'),
+    (KeyAction::ToggleLegend, "toggle-legend", 'L'),
+    (KeyAction::ToggleGrowth, "toggle-growth", 'd'),
+    (KeyAction::ToggleUnescape, "toggle-unescape", 'U'),
+    (KeyAction::ToggleReclaim, "toggle-reclaim", 'R'),
+    (KeyAction::ToggleState, "toggle-state", 'O'),
+    (KeyAction::ToggleIoRates, "toggle-io-rates", 'b'),
+    (KeyAction::ToggleThreads, "toggle-threads", 'a'),
+    (KeyAction::ToggleChildren, "toggle-children", 'c'),
+    (KeyAction::IncreaseLimit, "increase-limit", '+'),
+    (KeyAction::DecreaseLimit, "decrease-limit", '-'),
+    (KeyAction::ToggleWatch, "toggle-watch", 'w'),
+    (KeyAction::ToggleComm, "toggle-comm", 'm'),
+    (KeyAction::ToggleContainer, "toggle-container", 'C'),
+    (KeyAction::ToggleHistogram, "toggle-histogram", 'H'),
+    (KeyAction::ToggleUserColours, "toggle-user-colours", 'U'),
+    (KeyAction::ToggleProcTree, "toggle-proc-tree", 'A'),
+    (KeyAction::ProcDetail, "proc-detail", 'd'),
+    (KeyAction::EventsDetail, "events-detail", 'v'),
+    (KeyAction::FlatView, "flat-view", 'l'),
+    (KeyAction::Footprint, "footprint", 'S'),
+    (KeyAction::Search, "search", '/'),
+    (KeyAction::Filter, "filter", '/'),
+    (KeyAction::StateFilter, "state-filter", 'S'),
+    (KeyAction::WatchCGroup, "watch-cgroup", 'W'),
+    (KeyAction::GraphCGroup, "graph-cgroup", 'V'),
+    (KeyAction::TogglePause, "toggle-pause", 'Z'),
+    (KeyAction::Step, "step", '.'),
+    (KeyAction::ToggleUnits, "toggle-units", 'u'),
+    (KeyAction::ToggleRawBytes, "toggle-raw-bytes", 'B'),
+    (KeyAction::MarkReference, "mark-reference", 'M'),
+    (KeyAction::Terminate, "terminate", 'x'),
+    (KeyAction::Kill, "kill", 'K'),
+    (KeyAction::Freeze, "freeze", 'F'),
+    (KeyAction::EditNote, "edit-note", 'N'),
+    (KeyAction::CopyPath, "copy-path", 'y'),
+    (KeyAction::JumpOwnCGroup, "jump-own-cgroup", 'm'),
+];
+
+/// Maps keys to actions
+pub struct KeyMap {
+    keys: HashMap<KeyAction, char>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            keys: BINDINGS
+                .iter()
+                .map(|(action, _, key)| (*action, *key))
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Binds a key to a named action. Returns false if the action name is not known
+    pub fn bind(&mut self, name: &str, key: char) -> bool {
+        match BINDINGS.iter().find(|(_, n, _)| *n == name) {
+            Some((action, _, _)) => {
+                self.keys.insert(*action, key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Finds the action from the list of actions bound to a key
+    pub fn action(&self, code: KeyCode, actions: &[KeyAction]) -> Option<KeyAction> {
+        match code {
+            KeyCode::Char(c) => actions
+                .iter()
+                .find(|action| self.keys.get(action) == Some(&c))
+                .copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns the key bound to an action for display
+    pub fn key(&self, action: KeyAction) -> String {
+        self.keys
+            .get(&action)
+            .map(|c| c.to_string())
+            .unwrap_or_default()
+    }
+}
@@ -1,19 +1,32 @@
+pub mod keymap;
 mod scenes;
 
+use std::collections::HashMap;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEventKind};
 
+use self::keymap::KeyMap;
+use self::scenes::cgroup_events::CGroupEventsScene;
+use self::scenes::cgroup_flat::CGroupFlatScene;
+use self::scenes::cgroup_footprint::CGroupFootprintScene;
+use self::scenes::cgroup_graph::CGroupGraphScene;
 use self::scenes::cgroup_tree::CGroupTreeScene;
 use self::scenes::cgroup_tree_help::build_cgroup_tree_help_scene;
+use self::scenes::cgroup_watch::CGroupWatchScene;
 use self::scenes::help::HelpScene;
+use self::scenes::proc_detail::ProcDetailScene;
 use self::scenes::procs::ProcsScene;
 use self::scenes::procs_help::build_procs_help_scene;
 use self::scenes::stat_choose::StatChooseScene;
 use self::scenes::Scene;
 use super::TermType;
+use crate::cgroup::stats::{find_cgroup_stat, first_cgroup_stat, Stat};
 use crate::cgroup::CGroupSortOrder;
+use crate::config::State;
+use crate::formatters::MemUnits;
 use crate::proc::ProcSortOrder;
 
 type PollResult = Option<Vec<Action>>;
@@ -22,10 +35,18 @@ type PollResult = Option<Vec<Action>>;
 pub enum Action {
     Reload,
     Exit,
+    Pause,
+    ToggleUnits,
+    ToggleRawBytes,
     Stat(usize),
     Scene(AppScene),
     ProcCGroup(PathBuf),
     ProcMode(bool, bool),
+    ProcDetail(usize, String),
+    EventsCGroup(PathBuf),
+    TreeSelect(PathBuf),
+    WatchCGroup(PathBuf),
+    GraphCGroup(PathBuf),
     CGroupSort(CGroupSortOrder),
     ProcSort(ProcSortOrder),
 }
@@ -37,6 +58,25 @@ pub enum AppScene {
     StatChoose,
     Procs,
     ProcsHelp,
+    ProcDetail,
+    CGroupEvents,
+    CGroupFlat,
+    CGroupFootprint,
+    CGroupWatch,
+    CGroupGraph,
+}
+
+/// Options shared by the application scenes
+pub struct AppOptions {
+    pub keymap: KeyMap,
+    pub label: Option<String>,
+    pub root: PathBuf,
+    pub proc_limit: Option<usize>,
+    pub footprint_top: usize,
+    pub warn: Option<usize>,
+    pub proc_root: Option<PathBuf>,
+    pub interval: Duration,
+    pub debug: bool,
 }
 
 pub struct App<'a> {
@@ -44,33 +84,71 @@ pub struct App<'a> {
     terminal: &'a mut TermType,
     reload: bool,
     running: bool,
+    paused: bool,
+    units: MemUnits,
+    raw_bytes: bool,
+    stats: &'a [Stat<'static>],
+    stat: usize,
+    stat_sorts: HashMap<usize, (CGroupSortOrder, ProcSortOrder)>,
     cgroup_tree_scene: Box<CGroupTreeScene<'a>>,
     cgroup_tree_help_scene: Box<HelpScene<'a>>,
     stat_choose_scene: Box<StatChooseScene<'a>>,
     procs_scene: Box<ProcsScene<'a>>,
     procs_help_scene: Box<HelpScene<'a>>,
+    proc_detail_scene: Box<ProcDetailScene<'a>>,
+    cgroup_events_scene: Box<CGroupEventsScene<'a>>,
+    cgroup_flat_scene: Box<CGroupFlatScene<'a>>,
+    cgroup_footprint_scene: Box<CGroupFootprintScene<'a>>,
+    cgroup_watch_scene: Box<CGroupWatchScene<'a>>,
+    cgroup_graph_scene: Box<CGroupGraphScene<'a>>,
 }
 
 impl<'a> App<'a> {
     /// Creates the app
-    pub fn new(terminal: &'a mut TermType, cgroup2fs: &'a Path, stat: usize, debug: bool) -> Self {
+    pub fn new(
+        terminal: &'a mut TermType,
+        cgroup2fs: &'a Path,
+        stats: &'a [Stat<'static>],
+        state: State,
+        options: &'a AppOptions,
+    ) -> Self {
         let mut res = Self {
             scene: AppScene::CGroupTree,
             terminal,
             reload: true,
             running: true,
-            cgroup_tree_scene: Box::new(CGroupTreeScene::new(cgroup2fs, debug)),
-            cgroup_tree_help_scene: Box::new(build_cgroup_tree_help_scene()),
-            stat_choose_scene: Box::new(StatChooseScene::new()),
-            procs_scene: Box::new(ProcsScene::new(cgroup2fs, debug)),
-            procs_help_scene: Box::new(build_procs_help_scene()),
+            paused: false,
+            units: MemUnits::default(),
+            raw_bytes: false,
+            stats,
+            stat: first_cgroup_stat(stats),
+            stat_sorts: HashMap::new(),
+            cgroup_tree_scene: Box::new(CGroupTreeScene::new(cgroup2fs, stats, options)),
+            cgroup_tree_help_scene: Box::new(build_cgroup_tree_help_scene(options)),
+            stat_choose_scene: Box::new(StatChooseScene::new(stats, options)),
+            procs_scene: Box::new(ProcsScene::new(cgroup2fs, stats, options)),
+            procs_help_scene: Box::new(build_procs_help_scene(options)),
+            proc_detail_scene: Box::new(ProcDetailScene::new(options)),
+            cgroup_events_scene: Box::new(CGroupEventsScene::new(cgroup2fs, options)),
+            cgroup_flat_scene: Box::new(CGroupFlatScene::new(cgroup2fs, stats, options)),
+            cgroup_footprint_scene: Box::new(CGroupFootprintScene::new(cgroup2fs, options)),
+            cgroup_watch_scene: Box::new(CGroupWatchScene::new(cgroup2fs, stats, options)),
+            cgroup_graph_scene: Box::new(CGroupGraphScene::new(cgroup2fs, options)),
         };
 
         // Set initial statistic
-        res.set_stat(stat);
+        res.set_stat(find_cgroup_stat(stats, state.stat.as_deref()));
+
+        // Set initial sort orders. The process sort order is only restored in the process
+        // scene so it doesn't override the cgroup sort order, which may come from --sort
+        res.set_cgroup_sort(state.cgroup_sort.unwrap_or(CGroupSortOrder::StatDsc));
 
-        // Set initial sort order
-        res.set_cgroup_sort(CGroupSortOrder::StatDsc);
+        if let Some(sort) = state.proc_sort {
+            res.procs_scene.set_sort(sort);
+        }
+
+        // Forget the default sort orders remembered when setting the initial statistic
+        res.stat_sorts.clear();
 
         res
     }
@@ -84,6 +162,12 @@ impl<'a> App<'a> {
                 AppScene::StatChoose => &mut *self.stat_choose_scene,
                 AppScene::Procs => &mut *self.procs_scene,
                 AppScene::ProcsHelp => &mut *self.procs_help_scene,
+                AppScene::ProcDetail => &mut *self.proc_detail_scene,
+                AppScene::CGroupEvents => &mut *self.cgroup_events_scene,
+                AppScene::CGroupFlat => &mut *self.cgroup_flat_scene,
+                AppScene::CGroupFootprint => &mut *self.cgroup_footprint_scene,
+                AppScene::CGroupWatch => &mut *self.cgroup_watch_scene,
+                AppScene::CGroupGraph => &mut *self.cgroup_graph_scene,
             };
 
             if self.reload {
@@ -96,7 +180,7 @@ impl<'a> App<'a> {
             scene.draw(self.terminal)?;
 
             // Poll events
-            let actions = Self::poll(scene)?;
+            let actions = Self::poll(scene, self.paused)?;
 
             // Process actions
             self.process_actions(actions);
@@ -105,37 +189,16 @@ impl<'a> App<'a> {
         Ok(())
     }
 
-    fn poll(scene: &mut dyn Scene) -> Result<Vec<Action>, io::Error> {
+    fn poll(scene: &mut dyn Scene, paused: bool) -> Result<Vec<Action>, io::Error> {
         let result = loop {
-            let result = if let Some(duration) = scene.time_to_refresh() {
+            let result = if paused {
+                // Wait for an event without refreshing
+                Self::handle_event(scene, event::read()?)
+            } else if let Some(duration) = scene.time_to_refresh() {
                 // Wait for event for timeout period
                 if event::poll(duration)? {
                     // Got an event
-                    match event::read()? {
-                        Event::Key(key_event) => {
-                            // A key was pressed
-                            scene.key_event(key_event)
-                        }
-                        Event::Mouse(mouse_event) => {
-                            // Mouse event
-                            match mouse_event.kind {
-                                MouseEventKind::ScrollDown => scene
-                                    .key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
-                                MouseEventKind::ScrollUp => {
-                                    scene.key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE))
-                                }
-                                _ => None,
-                            }
-                        }
-                        Event::Resize(_, _) => {
-                            // Break out to redraw
-                            Some(vec![])
-                        }
-                        _ => {
-                            // All other events are ignored
-                            None
-                        }
-                    }
+                    Self::handle_event(scene, event::read()?)
                 } else {
                     // No event in the timeout period
                     Some(vec![Action::Reload])
@@ -153,17 +216,54 @@ impl<'a> App<'a> {
         Ok(result.unwrap())
     }
 
+    fn handle_event(scene: &mut dyn Scene, event: Event) -> PollResult {
+        match event {
+            Event::Key(key_event) => {
+                // A key was pressed
+                scene.key_event(key_event)
+            }
+            Event::Mouse(mouse_event) => {
+                // Mouse event
+                match mouse_event.kind {
+                    MouseEventKind::ScrollDown => {
+                        scene.key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE))
+                    }
+                    MouseEventKind::ScrollUp => {
+                        scene.key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE))
+                    }
+                    _ => None,
+                }
+            }
+            Event::Resize(_, _) => {
+                // Break out to redraw
+                Some(vec![])
+            }
+            _ => {
+                // All other events are ignored
+                None
+            }
+        }
+    }
+
     fn process_actions(&mut self, actions: Vec<Action>) {
         for action in actions {
             match action {
                 Action::Reload => self.reload = true,
                 Action::Exit => self.running = false,
+                Action::Pause => self.set_paused(!self.paused),
+                Action::ToggleUnits => self.set_units(self.units.toggle(), self.raw_bytes),
+                Action::ToggleRawBytes => self.set_units(self.units, !self.raw_bytes),
                 Action::Scene(scene) => self.set_scene(scene),
                 Action::Stat(item) => self.set_stat(item),
                 Action::ProcCGroup(cgroup) => self.set_cgroup(cgroup),
                 Action::ProcMode(threads, include_children) => {
                     self.set_procs_mode(threads, include_children)
                 }
+                Action::ProcDetail(pid, cmd) => self.proc_detail_scene.set_proc(pid, cmd),
+                Action::EventsCGroup(cgroup) => self.cgroup_events_scene.set_cgroup(cgroup),
+                Action::TreeSelect(cgroup) => self.cgroup_tree_scene.select_cgroup(&cgroup),
+                Action::WatchCGroup(cgroup) => self.cgroup_watch_scene.set_cgroup(cgroup),
+                Action::GraphCGroup(cgroup) => self.cgroup_graph_scene.set_cgroup(cgroup),
                 Action::CGroupSort(sort) => self.set_cgroup_sort(sort),
                 Action::ProcSort(sort) => self.set_proc_sort(sort),
             }
@@ -175,8 +275,59 @@ impl<'a> App<'a> {
         self.reload = true;
     }
 
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.cgroup_tree_scene.set_paused(paused);
+        self.procs_scene.set_paused(paused);
+    }
+
+    fn set_units(&mut self, units: MemUnits, raw_bytes: bool) {
+        self.units = units;
+        self.raw_bytes = raw_bytes;
+
+        // Exact byte counts override the unit system
+        let units = if raw_bytes { MemUnits::Bytes } else { units };
+
+        self.cgroup_tree_scene.set_units(units);
+        self.procs_scene.set_units(units);
+        self.proc_detail_scene.set_units(units);
+        self.cgroup_flat_scene.set_units(units);
+        self.cgroup_footprint_scene.set_units(units);
+        self.cgroup_watch_scene.set_units(units);
+        self.cgroup_graph_scene.set_units(units);
+    }
+
+    /// Returns a summary of the cgroups as last loaded
+    pub fn summary(&self) -> Option<String> {
+        self.cgroup_tree_scene.summary()
+    }
+
+    /// Returns the current display state for saving
+    pub fn state(&self) -> State {
+        State {
+            stat: Some(self.stats[self.stat].short_desc().to_string()),
+            cgroup_sort: Some(self.cgroup_tree_scene.sort()),
+            proc_sort: Some(self.procs_scene.proc_sort()),
+        }
+    }
+
     fn set_stat(&mut self, stat: usize) {
+        // Remember the sort orders used with the old statistic and restore those last used with
+        // the new one. The process scene falls back to sorting by PID if the new statistic
+        // has no per process value
+        self.stat_sorts.insert(
+            self.stat,
+            (self.cgroup_tree_scene.sort(), self.procs_scene.proc_sort()),
+        );
+
+        if let Some((cgroup_sort, proc_sort)) = self.stat_sorts.get(&stat).copied() {
+            self.set_cgroup_sort(cgroup_sort);
+            self.set_proc_sort(proc_sort);
+        }
+
+        self.stat = stat;
         self.cgroup_tree_scene.set_stat(stat);
+        self.cgroup_flat_scene.set_stat(stat);
         self.stat_choose_scene.set_stat(stat);
         self.procs_scene.set_stat(stat);
     }
@@ -199,3 +350,50 @@ impl<'a> App<'a> {
         self.procs_scene.set_mode(threads, include_children);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::CrosstermBackend;
+    use ratatui::layout::Rect;
+    use ratatui::{Terminal, TerminalOptions, Viewport};
+
+    use super::*;
+    use crate::backend::TermBackend;
+    use crate::cgroup::stats::STATS;
+
+    #[test]
+    fn cgroup_sort_wins_over_saved_proc_sort() {
+        // A fixed viewport avoids querying the terminal size
+        let mut terminal = Terminal::with_options(
+            TermBackend::new(CrosstermBackend::new(io::stdout()), false),
+            TerminalOptions {
+                viewport: Viewport::Fixed(Rect::new(0, 0, 80, 24)),
+            },
+        )
+        .unwrap();
+
+        let options = AppOptions {
+            keymap: KeyMap::default(),
+            label: None,
+            root: PathBuf::new(),
+            proc_limit: None,
+            footprint_top: 10,
+            warn: None,
+            proc_root: None,
+            interval: Duration::from_secs(1),
+            debug: false,
+        };
+
+        // The cgroup sort order as set by --sort with a process sort order from the state file
+        let state = State {
+            stat: None,
+            cgroup_sort: Some(CGroupSortOrder::NameAsc),
+            proc_sort: Some(ProcSortOrder::StatDsc),
+        };
+
+        let app = App::new(&mut terminal, Path::new("/"), &STATS, state, &options);
+
+        assert_eq!(app.cgroup_tree_scene.sort(), CGroupSortOrder::NameAsc);
+        assert_eq!(app.procs_scene.proc_sort(), ProcSortOrder::StatDsc);
+    }
+}
@@ -0,0 +1,153 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use super::{labelled_title, Scene};
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::cgroup::load_flat_keyed;
+use crate::formatters::format_thousands;
+use crate::TermType;
+
+/// Events always shown, in display order
+const EVENTS: [&str; 6] = ["low", "high", "max", "oom", "oom_kill", "oom_group_kill"];
+
+pub struct CGroupEventsScene<'a> {
+    label: Option<&'a str>,
+    interval: Duration,
+    cgroup2fs: &'a Path,
+    cgroup: PathBuf,
+    events: Result<Vec<(String, usize)>, String>,
+    local_events: Result<Vec<(String, usize)>, String>,
+    next_refresh: Instant,
+}
+
+impl<'a> CGroupEventsScene<'a> {
+    /// Creates a new cgroup events scene
+    pub fn new(cgroup2fs: &'a Path, options: &'a AppOptions) -> Self {
+        Self {
+            label: options.label.as_deref(),
+            interval: options.interval,
+            cgroup2fs,
+            cgroup: PathBuf::new(),
+            events: Ok(Vec::new()),
+            local_events: Ok(Vec::new()),
+            next_refresh: Instant::now(),
+        }
+    }
+
+    /// Sets the cgroup to show
+    pub fn set_cgroup(&mut self, cgroup: PathBuf) {
+        self.cgroup = cgroup;
+    }
+
+    fn load(&self, file: &str) -> Result<Vec<(String, usize)>, String> {
+        let mut path = self.cgroup2fs.to_path_buf();
+        path.extend(&self.cgroup);
+        path.push(file);
+
+        load_flat_keyed(&path).map_err(|e| format!("Unable to read {}: {}", file, e))
+    }
+
+    fn value_text(values: &Result<Vec<(String, usize)>, String>, event: &str) -> String {
+        match values {
+            Ok(values) => match values.iter().find(|(key, _)| key == event) {
+                Some((_, value)) => format_thousands(*value),
+                None => "-".into(),
+            },
+            Err(_) => "-".into(),
+        }
+    }
+}
+
+impl<'a> Scene for CGroupEventsScene<'a> {
+    /// Reloads the cgroup events
+    fn reload(&mut self) {
+        self.events = self.load("memory.events");
+        self.local_events = self.load("memory.events.local");
+
+        // Calculate next refresh time
+        self.next_refresh = Instant::now().checked_add(self.interval).unwrap();
+    }
+
+    /// Draws the cgroup events scene
+    fn draw(&mut self, terminal: &mut TermType) -> Result<(), io::Error> {
+        terminal.draw(|f| {
+            // Get the size of the frame
+            let size = f.size();
+
+            // Create the block
+            let block = Block::default()
+                .title(labelled_title(
+                    self.label,
+                    &format!(
+                        "Memory Events for /{} (press q to return)",
+                        self.cgroup.to_string_lossy()
+                    ),
+                ))
+                .borders(Borders::ALL);
+
+            // Build list of events including any not in the standard list
+            let mut events: Vec<&str> = EVENTS.to_vec();
+
+            for values in [&self.events, &self.local_events].into_iter().flatten() {
+                for (key, _) in values {
+                    if !events.contains(&key.as_str()) {
+                        events.push(key);
+                    }
+                }
+            }
+
+            // Build the text
+            let mut text = vec![Line::from(Span::styled(
+                format!("  {:<16}{:>16}{:>16}", "Event", "Hierarchical", "Local"),
+                Style::default().add_modifier(Modifier::BOLD),
+            ))];
+
+            for event in events {
+                text.push(Line::from(format!(
+                    "  {:<16}{:>16}{:>16}",
+                    event,
+                    Self::value_text(&self.events, event),
+                    Self::value_text(&self.local_events, event)
+                )));
+            }
+
+            for values in [&self.events, &self.local_events] {
+                if let Err(msg) = values {
+                    text.push(Line::from(""));
+                    text.push(Line::from(Span::styled(
+                        msg.clone(),
+                        Style::default().fg(Color::Red),
+                    )));
+                }
+            }
+
+            // Create the paragraph
+            let para = Paragraph::new(text).block(block);
+
+            // Draw the paragraph
+            f.render_widget(para, size);
+        })?;
+
+        Ok(())
+    }
+
+    /// Calculates the time left before the details should be reloaded, None returned if overdue
+    fn time_to_refresh(&self) -> Option<Duration> {
+        self.next_refresh.checked_duration_since(Instant::now())
+    }
+
+    /// Key event
+    fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(vec![Action::Scene(AppScene::CGroupTree)]),
+            KeyCode::Char('r') => Some(vec![Action::Reload]),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
+
+use super::{labelled_title, render_scrollbar, Scene};
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::cgroup::stats::{first_cgroup_stat, Stat};
+use crate::cgroup::{load_cgroups, CGroup, CGroupSortOrder, LoadOptions};
+use crate::formatters::{theme, MemUnits};
+use crate::TermType;
+
+/// A single cgroup in the flat overview
+struct FlatEntry {
+    path: PathBuf,
+    value: Result<usize, String>,
+}
+
+pub struct CGroupFlatScene<'a> {
+    options: &'a AppOptions,
+    cgroup2fs: &'a Path,
+    stats: &'a [Stat<'a>],
+    stat: usize,
+    entries: Vec<FlatEntry>,
+    state: ListState,
+    page_size: usize,
+    units: MemUnits,
+    next_refresh: Instant,
+}
+
+impl<'a> CGroupFlatScene<'a> {
+    /// Creates a new flat overview scene
+    pub fn new(cgroup2fs: &'a Path, stats: &'a [Stat<'a>], options: &'a AppOptions) -> Self {
+        Self {
+            options,
+            cgroup2fs,
+            stats,
+            stat: first_cgroup_stat(stats),
+            entries: Vec::new(),
+            state: ListState::default(),
+            page_size: 1,
+            units: MemUnits::default(),
+            next_refresh: Instant::now(),
+        }
+    }
+
+    /// Sets the statistic to view. Process only statistics are ignored
+    pub fn set_stat(&mut self, stat: usize) {
+        if !self.stats[stat].proc_only() {
+            self.stat = stat
+        }
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+    }
+
+    /// Adds all cgroups in the hierarchy to the entry list
+    fn flatten(cgroups: &[CGroup], entries: &mut Vec<FlatEntry>) {
+        for cg in cgroups {
+            // Skip the pseudo nodes for processes in inner cgroups
+            if !cg.path().ends_with("<self>") {
+                entries.push(FlatEntry {
+                    path: cg.path().clone(),
+                    value: match cg.error() {
+                        Some(msg) => Err(msg.clone()),
+                        None => Ok(cg.stat()),
+                    },
+                });
+            }
+
+            Self::flatten(cg.children(), entries);
+        }
+    }
+
+    fn move_to(&mut self, pos: isize) -> PollResult {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let pos = pos.clamp(0, self.entries.len() as isize - 1) as usize;
+
+        if self.state.selected() == Some(pos) {
+            None
+        } else {
+            self.state.select(Some(pos));
+            Some(vec![])
+        }
+    }
+
+    fn move_by(&mut self, amount: isize, no_pos: isize) -> PollResult {
+        let pos = match self.state.selected() {
+            Some(pos) => pos as isize + amount,
+            None => no_pos + amount,
+        };
+
+        self.move_to(pos)
+    }
+
+    #[must_use]
+    fn select(&mut self) -> PollResult {
+        let entry = self.entries.get(self.state.selected()?)?;
+
+        Some(vec![
+            Action::TreeSelect(entry.path.clone()),
+            Action::Scene(AppScene::CGroupTree),
+        ])
+    }
+}
+
+impl<'a> Scene for CGroupFlatScene<'a> {
+    /// Reloads the flat overview
+    fn reload(&mut self) {
+        // Save currently selected path
+        let old_selected = self
+            .state
+            .selected()
+            .and_then(|pos| self.entries.get(pos))
+            .map(|entry| entry.path.clone());
+
+        // Load and flatten the hierarchy
+        let cgroups = load_cgroups(
+            self.cgroup2fs,
+            &self.options.root,
+            &self.stats[self.stat],
+            CGroupSortOrder::NameAsc,
+            LoadOptions::default(),
+        )
+        .unwrap_or_default();
+
+        let mut entries = Vec::new();
+        Self::flatten(&cgroups, &mut entries);
+
+        // Sort by value descending with errors at the end
+        entries.sort_by(|a, b| match (&a.value, &b.value) {
+            (Ok(a_value), Ok(b_value)) => b_value.cmp(a_value),
+            (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+            (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+            (Err(_), Err(_)) => a.path.cmp(&b.path),
+        });
+
+        self.entries = entries;
+
+        // Restore the selection
+        self.state
+            .select(old_selected.and_then(|path| self.entries.iter().position(|e| e.path == path)));
+
+        // Calculate next refresh time
+        self.next_refresh = Instant::now().checked_add(self.options.interval).unwrap();
+    }
+
+    /// Draws the flat overview scene
+    fn draw(&mut self, terminal: &mut TermType) -> Result<(), io::Error> {
+        terminal.draw(|f| {
+            // Get the size of the frame
+            let size = f.size();
+
+            self.page_size = std::cmp::max(3, size.height as usize) - 2;
+
+            // Create the block
+            let block = Block::default()
+                .title(labelled_title(
+                    self.options.label.as_deref(),
+                    &format!(
+                        "All CGroups by {} (press Enter to show in tree, q to return)",
+                        self.stats[self.stat].short_desc()
+                    ),
+                ))
+                .borders(Borders::ALL);
+
+            // Build the list items
+            let format = self.stats[self.stat].format();
+
+            let items: Vec<ListItem> = self
+                .entries
+                .iter()
+                .map(|entry| {
+                    let path = Span::from(format!(" /{}", entry.path.to_string_lossy()));
+
+                    ListItem::new(Line::from(match &entry.value {
+                        Ok(value) => vec![format.format(*value, self.units), path],
+                        Err(msg) => vec![
+                            Span::styled(
+                                format!("{:>9}", "<Error>"),
+                                Style::default().fg(Color::Red),
+                            ),
+                            path,
+                            Span::styled(format!(" ({})", msg), Style::default().fg(Color::Red)),
+                        ],
+                    }))
+                })
+                .collect();
+
+            // Create the list
+            let list = List::new(items)
+                .block(block)
+                .highlight_style(theme().highlight);
+
+            // Draw the list
+            f.render_stateful_widget(list, size, &mut self.state);
+
+            render_scrollbar(
+                f,
+                size,
+                self.entries.len(),
+                self.page_size,
+                self.state.selected().unwrap_or(0),
+            );
+        })?;
+
+        Ok(())
+    }
+
+    /// Calculates the time left before the list should be reloaded, None returned if overdue
+    fn time_to_refresh(&self) -> Option<Duration> {
+        self.next_refresh.checked_duration_since(Instant::now())
+    }
+
+    /// Key event
+    fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(vec![Action::Scene(AppScene::CGroupTree)]),
+            KeyCode::Char('r') => Some(vec![Action::Reload]),
+            KeyCode::Down => self.move_by(1, -1),
+            KeyCode::Up => self.move_by(-1, 0),
+            KeyCode::PageDown => self.move_by(self.page_size as isize, -1),
+            KeyCode::PageUp => self.move_by(-(self.page_size as isize), 0),
+            KeyCode::Home => self.move_to(0),
+            KeyCode::End => self.move_to(isize::MAX),
+            KeyCode::Enter => self.select(),
+            _ => None,
+        }
+    }
+}
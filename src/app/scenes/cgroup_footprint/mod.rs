@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use super::{labelled_title, Scene};
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::cgroup::stats::{Stat, STATS};
+use crate::cgroup::{load_cgroups, CGroup, CGroupSortOrder, LoadOptions};
+use crate::formatters::{format_mem_qty, MemUnits};
+use crate::TermType;
+
+/// Colour of the memory part of the bars
+const MEMORY_COLOUR: Color = Color::LightBlue;
+
+/// Colour of the swap part of the bars
+const SWAP_COLOUR: Color = Color::LightMagenta;
+
+/// A single cgroup in the footprint dashboard
+struct FootprintEntry {
+    path: PathBuf,
+    memory: usize,
+    swap: usize,
+}
+
+pub struct CGroupFootprintScene<'a> {
+    options: &'a AppOptions,
+    cgroup2fs: &'a Path,
+    memory_stat: Stat<'static>,
+    swap_stat: Stat<'static>,
+    top: usize,
+    entries: Vec<FootprintEntry>,
+    units: MemUnits,
+    next_refresh: Instant,
+}
+
+impl<'a> CGroupFootprintScene<'a> {
+    /// Creates a new footprint dashboard scene
+    pub fn new(cgroup2fs: &'a Path, options: &'a AppOptions) -> Self {
+        Self {
+            options,
+            cgroup2fs,
+            memory_stat: *Self::find_stat("memory.current"),
+            swap_stat: *Self::find_stat("memory.swap.current"),
+            top: options.footprint_top,
+            entries: Vec::new(),
+            units: MemUnits::default(),
+            next_refresh: Instant::now(),
+        }
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+    }
+
+    fn find_stat(def: &str) -> &'static Stat<'static> {
+        STATS.iter().find(|stat| stat.def() == Some(def)).unwrap()
+    }
+
+    /// Loads the values of a statistic for all cgroups by path, skipping cgroups in error
+    fn load_values(&self, stat: &Stat) -> HashMap<PathBuf, usize> {
+        let cgroups = load_cgroups(
+            self.cgroup2fs,
+            &self.options.root,
+            stat,
+            CGroupSortOrder::NameAsc,
+            LoadOptions::default(),
+        )
+        .unwrap_or_default();
+
+        let mut values = HashMap::new();
+        Self::flatten(&cgroups, &mut values);
+
+        values
+    }
+
+    /// Adds the values of all cgroups in the hierarchy to the map
+    fn flatten(cgroups: &[CGroup], values: &mut HashMap<PathBuf, usize>) {
+        for cg in cgroups {
+            // Skip the root and the pseudo nodes for processes in inner cgroups
+            if !cg.path().as_os_str().is_empty()
+                && !cg.path().ends_with("<self>")
+                && cg.error().is_none()
+            {
+                values.insert(cg.path().clone(), cg.stat());
+            }
+
+            Self::flatten(cg.children(), values);
+        }
+    }
+
+    #[must_use]
+    fn change_top(&mut self, increase: bool) -> PollResult {
+        self.top = if increase {
+            self.top + 1
+        } else {
+            std::cmp::max(1, self.top - 1)
+        };
+
+        Some(vec![Action::Reload])
+    }
+
+    /// Builds the line for an entry with a bar of the given width scaled to the largest total
+    fn entry_line(
+        &self,
+        entry: &FootprintEntry,
+        name_len: usize,
+        bar_width: usize,
+        max_total: usize,
+    ) -> Line<'static> {
+        let cells = |value: usize| {
+            if max_total == 0 {
+                0
+            } else {
+                (value as f64 * bar_width as f64 / max_total as f64).round() as usize
+            }
+        };
+
+        let memory_cells = cells(entry.memory);
+        let swap_cells = std::cmp::min(
+            cells(entry.memory + entry.swap).saturating_sub(memory_cells),
+            bar_width - memory_cells,
+        );
+
+        // Truncate long names to fit
+        let name: String = format!("/{}", entry.path.to_string_lossy())
+            .chars()
+            .take(name_len)
+            .collect();
+
+        Line::from(vec![
+            Span::from(format!(" {:<1$} ", name, name_len)),
+            Span::styled("█".repeat(memory_cells), Style::default().fg(MEMORY_COLOUR)),
+            Span::styled("█".repeat(swap_cells), Style::default().fg(SWAP_COLOUR)),
+            Span::from(" ".repeat(bar_width - memory_cells - swap_cells + 1)),
+            format_mem_qty(entry.memory, self.units),
+            Span::from(" + "),
+            format_mem_qty(entry.swap, self.units),
+        ])
+    }
+}
+
+impl<'a> Scene for CGroupFootprintScene<'a> {
+    /// Reloads the memory and swap usage of all cgroups
+    fn reload(&mut self) {
+        let memory = self.load_values(&self.memory_stat);
+        let swap = self.load_values(&self.swap_stat);
+
+        let mut entries: Vec<FootprintEntry> = memory
+            .into_iter()
+            .map(|(path, memory)| FootprintEntry {
+                swap: swap.get(&path).copied().unwrap_or(0),
+                path,
+                memory,
+            })
+            .collect();
+
+        // Sort by total footprint descending
+        entries.sort_by(|a, b| {
+            (b.memory + b.swap)
+                .cmp(&(a.memory + a.swap))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        entries.truncate(self.top);
+
+        self.entries = entries;
+
+        // Calculate next refresh time
+        self.next_refresh = Instant::now().checked_add(self.options.interval).unwrap();
+    }
+
+    /// Draws the footprint dashboard scene
+    fn draw(&mut self, terminal: &mut TermType) -> Result<(), io::Error> {
+        terminal.draw(|f| {
+            // Get the size of the frame
+            let size = f.size();
+
+            // Create the block
+            let block = Block::default()
+                .title(labelled_title(
+                    self.options.label.as_deref(),
+                    &format!(
+                        "Top {} CGroups by Memory + Swap (press +/- to change, q to return)",
+                        self.top
+                    ),
+                ))
+                .borders(Borders::ALL);
+
+            // Build the text
+            let mut text = vec![Line::from(vec![
+                Span::from(" "),
+                Span::styled("█", Style::default().fg(MEMORY_COLOUR)),
+                Span::from(" Memory  "),
+                Span::styled("█", Style::default().fg(SWAP_COLOUR)),
+                Span::from(" Swap"),
+            ])];
+
+            text.push(Line::from(""));
+
+            if self.entries.is_empty() {
+                text.push(Line::from(Span::styled(
+                    " No cgroups with memory statistics",
+                    Style::default().add_modifier(Modifier::DIM),
+                )));
+            } else {
+                // Split the width between the names, bars and values
+                let inner_width = size.width.saturating_sub(2) as usize;
+
+                let values_len = format_mem_qty(0, self.units).width() * 2 + 3;
+
+                let name_len = self
+                    .entries
+                    .iter()
+                    .map(|entry| entry.path.to_string_lossy().chars().count() + 1)
+                    .max()
+                    .unwrap_or(0)
+                    .min(inner_width * 2 / 5);
+
+                let bar_width = inner_width.saturating_sub(name_len + values_len + 3);
+
+                let max_total = self
+                    .entries
+                    .iter()
+                    .map(|entry| entry.memory + entry.swap)
+                    .max()
+                    .unwrap_or(0);
+
+                text.extend(
+                    self.entries
+                        .iter()
+                        .map(|entry| self.entry_line(entry, name_len, bar_width, max_total)),
+                );
+            }
+
+            // Create the paragraph
+            let para = Paragraph::new(text).block(block);
+
+            // Draw the paragraph
+            f.render_widget(para, size);
+        })?;
+
+        Ok(())
+    }
+
+    /// Calculates the time left before the dashboard should be reloaded, None returned if overdue
+    fn time_to_refresh(&self) -> Option<Duration> {
+        self.next_refresh.checked_duration_since(Instant::now())
+    }
+
+    /// Key event
+    fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(vec![Action::Scene(AppScene::CGroupTree)]),
+            KeyCode::Char('r') => Some(vec![Action::Reload]),
+            KeyCode::Char('+') => self.change_top(true),
+            KeyCode::Char('-') => self.change_top(false),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Sparkline};
+
+use super::{labelled_title, Scene};
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::file_proc::{get_file_processor, FileProcessor};
+use crate::formatters::{format_mem_qty, MemUnits};
+use crate::TermType;
+
+/// Maximum number of samples kept
+const HISTORY_LEN: usize = 1024;
+
+/// Colour of the graph
+const GRAPH_COLOUR: Color = Color::LightBlue;
+
+pub struct CGroupGraphScene<'a> {
+    label: Option<&'a str>,
+    interval: Duration,
+    cgroup2fs: &'a Path,
+    processor: Box<dyn FileProcessor>,
+    cgroup: PathBuf,
+    samples: VecDeque<usize>,
+    error: Option<String>,
+    units: MemUnits,
+    next_refresh: Instant,
+}
+
+impl<'a> CGroupGraphScene<'a> {
+    /// Creates a new memory graph scene
+    pub fn new(cgroup2fs: &'a Path, options: &'a AppOptions) -> Self {
+        Self {
+            label: options.label.as_deref(),
+            interval: options.interval,
+            cgroup2fs,
+            processor: get_file_processor("memory.current").unwrap(),
+            cgroup: PathBuf::new(),
+            samples: VecDeque::with_capacity(HISTORY_LEN),
+            error: None,
+            units: MemUnits::default(),
+            next_refresh: Instant::now(),
+        }
+    }
+
+    /// Sets the cgroup to graph. The history is kept if the cgroup is unchanged
+    pub fn set_cgroup(&mut self, cgroup: PathBuf) {
+        if cgroup != self.cgroup {
+            self.samples.clear();
+        }
+
+        self.cgroup = cgroup;
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+    }
+
+    /// Builds the line of current, minimum and maximum values
+    fn values_line(&self) -> Line<'static> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        let (Some(current), Some(min), Some(max)) = (
+            self.samples.back(),
+            self.samples.iter().min(),
+            self.samples.iter().max(),
+        ) else {
+            return Line::from(Span::styled(
+                " No samples",
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        };
+
+        Line::from(vec![
+            Span::styled(" Current ", bold),
+            format_mem_qty(*current, self.units),
+            Span::styled("  Min ", bold),
+            format_mem_qty(*min, self.units),
+            Span::styled("  Max ", bold),
+            format_mem_qty(*max, self.units),
+            Span::styled(
+                format!("  ({} samples)", self.samples.len()),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ])
+    }
+}
+
+impl<'a> Scene for CGroupGraphScene<'a> {
+    /// Samples the memory usage of the cgroup
+    fn reload(&mut self) {
+        let mut path = self.cgroup2fs.to_path_buf();
+        path.extend(&self.cgroup);
+
+        match self.processor.get_stat(&path) {
+            Ok(value) => {
+                if self.samples.len() == HISTORY_LEN {
+                    self.samples.pop_front();
+                }
+
+                self.samples.push_back(value);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+
+        // Calculate next refresh time
+        self.next_refresh = Instant::now().checked_add(self.interval).unwrap();
+    }
+
+    /// Draws the memory graph scene
+    fn draw(&mut self, terminal: &mut TermType) -> Result<(), io::Error> {
+        terminal.draw(|f| {
+            // Get the size of the frame
+            let size = f.size();
+
+            // Create the block
+            let block = Block::default()
+                .title(labelled_title(
+                    self.label,
+                    &format!(
+                        "Memory Usage of /{} every {:?} (press q to return)",
+                        self.cgroup.to_string_lossy(),
+                        self.interval
+                    ),
+                ))
+                .borders(Borders::ALL);
+
+            let inner = block.inner(size);
+
+            f.render_widget(block, size);
+
+            // Split the inside between the values and the graph
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(2), Constraint::Min(0)])
+                .split(inner);
+
+            let line = match &self.error {
+                Some(msg) => Line::from(Span::styled(
+                    format!(" {}", msg),
+                    Style::default().fg(Color::Red),
+                )),
+                None => self.values_line(),
+            };
+
+            f.render_widget(Paragraph::new(line), chunks[0]);
+
+            // Graph the most recent samples that fit
+            let data: Vec<u64> = self
+                .samples
+                .iter()
+                .skip(self.samples.len().saturating_sub(chunks[1].width as usize))
+                .map(|value| *value as u64)
+                .collect();
+
+            let sparkline = Sparkline::default()
+                .data(&data)
+                .max(data.iter().max().copied().unwrap_or(0))
+                .style(Style::default().fg(GRAPH_COLOUR));
+
+            f.render_widget(sparkline, chunks[1]);
+        })?;
+
+        Ok(())
+    }
+
+    /// Calculates the time left before the next sample, None returned if overdue
+    fn time_to_refresh(&self) -> Option<Duration> {
+        self.next_refresh.checked_duration_since(Instant::now())
+    }
+
+    /// Key event
+    fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(vec![Action::Scene(AppScene::CGroupTree)]),
+            KeyCode::Char('r') => Some(vec![Action::Reload]),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::Frame;
+
+use crate::app::scenes::render_popup;
+use crate::cgroup::stats::{Stat, STATS};
+use crate::file_proc::{get_file_processor, FileProcessorError};
+use crate::formatters::MemUnits;
+
+/// Statistics shown in the details popup
+const DETAIL_STATS: [&str; 6] = [
+    "memory.current",
+    "memory.swap.current",
+    "memory.stat/=/1/anon/2",
+    "memory.stat/=/1/file/2",
+    "memory.stat/=/1/pagetables/2",
+    "cgroup.procs/#",
+];
+
+/// Popup over the tree summarising the main statistics of a single cgroup
+pub struct CGroupDetailsPopup {
+    path: PathBuf,
+    values: Vec<(&'static Stat<'static>, Result<usize, FileProcessorError>)>,
+}
+
+impl CGroupDetailsPopup {
+    /// Creates the popup for a cgroup. The statistics are loaded by reload
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            values: Vec::new(),
+        }
+    }
+
+    /// Loads the statistics for the cgroup
+    pub fn reload(&mut self, cgroup2fs: &Path) {
+        let abs_path = cgroup2fs.join(&self.path);
+
+        self.values = DETAIL_STATS
+            .iter()
+            .filter_map(|def| STATS.iter().find(|stat| stat.def() == Some(*def)))
+            .map(|stat| {
+                let value = stat
+                    .def()
+                    .and_then(get_file_processor)
+                    .ok_or(FileProcessorError::ValueNotFound)
+                    .and_then(|processor| processor.get_stat(&abs_path));
+
+                (stat, value)
+            })
+            .collect();
+    }
+
+    pub fn render(&self, frame: &mut Frame, units: MemUnits) {
+        let name_len = self
+            .values
+            .iter()
+            .map(|(stat, _)| stat.short_desc().chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let text = self
+            .values
+            .iter()
+            .map(|(stat, value)| {
+                let value = match value {
+                    Ok(value) => stat.format().format(*value, units),
+                    Err(e) => Span::styled(e.to_string(), Style::default().fg(Color::Red)),
+                };
+
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {:<1$}  ", stat.short_desc(), name_len),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    value,
+                ])
+            })
+            .collect();
+
+        render_popup(
+            frame,
+            &format!("/{} (Esc to close)", self.path.to_string_lossy()),
+            text,
+        );
+    }
+}
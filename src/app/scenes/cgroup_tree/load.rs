@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Instant;
+use std::{io, thread};
+
+use crate::cgroup::stats::Stat;
+use crate::cgroup::{load_cgroups, CGroup, CGroupSortOrder, LoadOptions};
+
+/// Everything that determines the cgroups loaded for the trees
+#[derive(Clone, PartialEq, Eq)]
+pub struct LoadParams {
+    pub roots: Vec<PathBuf>,
+    pub stat: usize,
+    pub sort: CGroupSortOrder,
+    pub options: LoadOptions,
+}
+
+/// Load of the cgroups for each tree running on a background thread
+pub struct PendingLoad {
+    pub params: LoadParams,
+    pub start: Instant,
+    pub receiver: Receiver<io::Result<Vec<Vec<CGroup>>>>,
+}
+
+impl PendingLoad {
+    /// Starts loading the cgroups under each root
+    pub fn start(cgroup2fs: &Path, stat: Stat<'static>, params: LoadParams) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        let cgroup2fs = cgroup2fs.to_path_buf();
+        let thread_params = params.clone();
+
+        thread::spawn(move || {
+            let trees: io::Result<Vec<Vec<CGroup>>> = thread_params
+                .roots
+                .iter()
+                .map(|root| {
+                    load_cgroups(
+                        &cgroup2fs,
+                        root,
+                        &stat,
+                        thread_params.sort,
+                        thread_params.options,
+                    )
+                })
+                .collect();
+
+            // The receiver is dropped if the load was abandoned
+            let _ = sender.send(trees);
+        });
+
+        Self {
+            params,
+            start: Instant::now(),
+            receiver,
+        }
+    }
+}
@@ -1,49 +1,213 @@
+mod details;
+mod load;
 mod tree;
 
-use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
 use std::time::{Duration, Instant};
+use std::{fs, io};
 
 use crossterm::event::{KeyCode, KeyEvent};
-use ratatui::widgets::{Block, Borders};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::block::{Position, Title};
+use ratatui::widgets::{Block, Borders, Paragraph};
 
+use self::details::CGroupDetailsPopup;
+use self::load::{LoadParams, PendingLoad};
 use self::tree::CGroupTree;
-use super::Scene;
-use crate::app::{Action, AppScene, PollResult};
-use crate::cgroup::stats::{StatType, STATS};
-use crate::cgroup::CGroupSortOrder;
+use super::{labelled_title, slow_reload_warning, vim_nav_code, Scene};
+use crate::app::keymap::KeyAction;
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::cgroup::stats::{first_cgroup_stat, Stat, StatFormat};
+use crate::cgroup::{cgroup_frozen, own_cgroup, set_cgroup_frozen, CGroupSortOrder, LoadOptions};
+use crate::clipboard::copy_to_clipboard;
+use crate::config::Notes;
+use crate::file_proc::get_file_processor;
+use crate::formatters::{colour_powers, export_file_name, theme, MemUnits, POWERS};
 use crate::proc::ProcSortOrder;
 use crate::TermType;
 
+/// Key actions available in the cgroup tree scene
+const KEY_ACTIONS: [KeyAction; 44] = [
+    KeyAction::Quit,
+    KeyAction::CollapseAll,
+    KeyAction::Split,
+    KeyAction::Reload,
+    KeyAction::TogglePause,
+    KeyAction::ToggleUnits,
+    KeyAction::ToggleRawBytes,
+    KeyAction::Step,
+    KeyAction::SortName,
+    KeyAction::SortStat,
+    KeyAction::ShowProcs,
+    KeyAction::ShowThreads,
+    KeyAction::ShowHierProcs,
+    KeyAction::ShowHierThreads,
+    KeyAction::EventsDetail,
+    KeyAction::WatchCGroup,
+    KeyAction::GraphCGroup,
+    KeyAction::Freeze,
+    KeyAction::EditNote,
+    KeyAction::CopyPath,
+    KeyAction::JumpOwnCGroup,
+    KeyAction::FlatView,
+    KeyAction::Footprint,
+    KeyAction::Search,
+    KeyAction::ToggleOomGroup,
+    KeyAction::ToggleTaskCounts,
+    KeyAction::ToggleProcAverage,
+    KeyAction::ToggleInode,
+    KeyAction::ToggleSubtreeControl,
+    KeyAction::TogglePopulatedOnly,
+    KeyAction::ToggleHideEmpty,
+    KeyAction::ToggleLegend,
+    KeyAction::ToggleGrowth,
+    KeyAction::ToggleUnescape,
+    KeyAction::ToggleReclaim,
+    KeyAction::ToggleState,
+    KeyAction::ToggleIoRates,
+    KeyAction::MarkReference,
+    KeyAction::ChooseStat,
+    KeyAction::PrevStat,
+    KeyAction::NextStat,
+    KeyAction::Export,
+    KeyAction::ExportJson,
+    KeyAction::Help,
+];
+
+/// Time to wait for a load to finish before showing the loading indicator
+const LOAD_WAIT: Duration = Duration::from_millis(100);
+
+/// Time between updates of the loading indicator
+const SPINNER_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Loading indicator frames
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
 pub struct CGroupTreeScene<'a> {
-    debug: bool,
+    options: &'a AppOptions,
     cgroup2fs: &'a Path,
+    stats: &'a [Stat<'static>],
     tree: CGroupTree<'a>,
+    split: Option<CGroupTree<'a>>,
+    split_active: bool,
     next_refresh: Instant,
+    load_time: Duration,
     draws: usize,
     loads: usize,
     sort: CGroupSortOrder,
     stat: usize,
+    load_options: LoadOptions,
+    growth: bool,
+    status: Option<String>,
+    paused: bool,
+    units: MemUnits,
+    search: Option<String>,
+    search_input: bool,
+    unescape: bool,
+    reference: Option<PathBuf>,
+    swap_configured: bool,
+    pending_freeze: Option<(PathBuf, bool)>,
+    notes: Notes,
+    note_input: Option<(PathBuf, String)>,
+    own_cgroup: Option<PathBuf>,
+    hide_empty: bool,
+    legend: bool,
+    details: Option<CGroupDetailsPopup>,
+    loading: Option<PendingLoad>,
+    spinner: usize,
+    unavailable: Option<String>,
+    retry: bool,
 }
 
 impl<'a> CGroupTreeScene<'a> {
     /// Creates a new cgroup tree scene
-    pub fn new(cgroup2fs: &'a Path, debug: bool) -> Self {
+    pub fn new(cgroup2fs: &'a Path, stats: &'a [Stat<'static>], options: &'a AppOptions) -> Self {
+        let mut tree = CGroupTree::default();
+        tree.set_root(options.root.clone());
+        tree.set_warn(options.warn);
+
+        // This process isn't in the tree if processes aren't being looked up
+        let own_cgroup = options.proc_root.as_deref().and_then(own_cgroup);
+        tree.set_own_cgroup(own_cgroup.clone());
+
         Self {
-            debug,
+            options,
             cgroup2fs,
-            tree: Default::default(),
+            stats,
+            tree,
+            split: None,
+            split_active: false,
             next_refresh: Instant::now(),
+            load_time: Duration::ZERO,
             draws: 0,
             loads: 0,
             sort: CGroupSortOrder::NameAsc,
-            stat: 0,
+            stat: first_cgroup_stat(stats),
+            load_options: LoadOptions::default(),
+            growth: false,
+            status: None,
+            paused: false,
+            units: MemUnits::default(),
+            search: None,
+            search_input: false,
+            unescape: false,
+            reference: None,
+            swap_configured: swap_configured(options.proc_root.as_deref()),
+            pending_freeze: None,
+            notes: Notes::load_default(),
+            note_input: None,
+            own_cgroup,
+            hide_empty: false,
+            legend: false,
+            details: None,
+            loading: None,
+            spinner: 0,
+            unavailable: None,
+            retry: false,
         }
     }
 
-    /// Sets the statistic to view
+    /// Sets whether automatic refreshes are paused
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+    }
+
+    /// Sets the statistic to view. Process only statistics are ignored
     pub fn set_stat(&mut self, stat: usize) {
-        self.stat = stat
+        if !self.stats[stat].proc_only() {
+            self.stat = stat
+        }
+    }
+
+    /// Selects a cgroup in the active tree, expanding its ancestors
+    pub fn select_cgroup(&mut self, path: &Path) {
+        self.active_tree_mut().select_path(path);
+    }
+
+    /// Returns a one line summary of the largest cgroup for the current statistic
+    pub fn summary(&self) -> Option<String> {
+        let stat = &self.stats[self.stat];
+        let cg = self.tree.top_cgroup()?;
+
+        Some(format!(
+            "Top cgroup by {}: /{} {}",
+            stat.short_desc(),
+            cg.path().to_string_lossy(),
+            stat.format().format(cg.stat(), self.units).content.trim()
+        ))
+    }
+
+    /// Returns the sort order in use
+    pub fn sort(&self) -> CGroupSortOrder {
+        self.sort
     }
 
     /// Sets the sort order to use
@@ -84,20 +248,462 @@ impl<'a> CGroupTreeScene<'a> {
 
     #[must_use]
     fn next_stat(&self, up: bool) -> PollResult {
-        let new_stat = if up {
-            (self.stat + 1) % STATS.len()
-        } else if self.stat == 0 {
-            STATS.len() - 1
+        let mut new_stat = self.stat;
+
+        loop {
+            new_stat = if up {
+                (new_stat + 1) % self.stats.len()
+            } else if new_stat == 0 {
+                self.stats.len() - 1
+            } else {
+                new_stat - 1
+            };
+
+            if !self.stats[new_stat].proc_only() {
+                break;
+            }
+        }
+
+        Some(vec![Action::Stat(new_stat), Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_oom_group(&mut self) -> PollResult {
+        self.load_options.oom_group = !self.load_options.oom_group;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_task_counts(&mut self) -> PollResult {
+        self.load_options.task_counts = !self.load_options.task_counts;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_proc_average(&mut self) -> PollResult {
+        self.load_options.proc_average = !self.load_options.proc_average;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_inode(&mut self) -> PollResult {
+        self.load_options.inode = !self.load_options.inode;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_subtree_control(&mut self) -> PollResult {
+        self.load_options.subtree_control = !self.load_options.subtree_control;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_hide_empty(&mut self) -> PollResult {
+        self.hide_empty = !self.hide_empty;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_legend(&mut self) -> PollResult {
+        self.legend = !self.legend;
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn toggle_populated_only(&mut self) -> PollResult {
+        self.load_options.populated_only = !self.load_options.populated_only;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_reclaim(&mut self) -> PollResult {
+        // Reclaim is judged from the memory usage of cgroups throttled by memory.high
+        self.load_options.high_events = !self.load_options.high_events;
+        self.load_options.usage = self.load_options.high_events;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_state(&mut self) -> PollResult {
+        self.load_options.event_flags = !self.load_options.event_flags;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_io_rates(&mut self) -> PollResult {
+        self.load_options.io_bytes = !self.load_options.io_bytes;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_unescape(&mut self) -> PollResult {
+        self.unescape = !self.unescape;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn toggle_growth(&mut self) -> PollResult {
+        self.growth = !self.growth;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn mark_reference(&mut self) -> PollResult {
+        let path = self.active_tree().cgroup()?.path().clone();
+
+        // Marking the current reference again clears it
+        if self.reference.as_ref() == Some(&path) {
+            self.reference = None;
         } else {
-            self.stat - 1
+            self.reference = Some(path);
+        }
+
+        Some(vec![Action::Reload])
+    }
+
+    /// Returns the tree which has keyboard focus
+    fn active_tree(&self) -> &CGroupTree<'a> {
+        match &self.split {
+            Some(split) if self.split_active => split,
+            _ => &self.tree,
+        }
+    }
+
+    /// Returns the tree which has keyboard focus
+    fn active_tree_mut(&mut self) -> &mut CGroupTree<'a> {
+        match &mut self.split {
+            Some(split) if self.split_active => split,
+            _ => &mut self.tree,
+        }
+    }
+
+    #[must_use]
+    fn open_split(&mut self) -> PollResult {
+        let mut root = self.active_tree().cgroup()?.path().clone();
+
+        // Use the parent of <self> nodes
+        if root.ends_with("<self>") {
+            root.pop();
+        }
+
+        let mut split = CGroupTree::default();
+        split.set_root(root);
+        split.set_warn(self.options.warn);
+        split.set_own_cgroup(self.own_cgroup.clone());
+
+        self.split = Some(split);
+        self.split_active = true;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn close_split(&mut self) -> PollResult {
+        self.split = None;
+        self.split_active = false;
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn switch_pane(&mut self) -> PollResult {
+        if self.split.is_some() {
+            self.split_active = !self.split_active;
+            Some(vec![])
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    fn start_search(&mut self) -> PollResult {
+        self.search = Some(String::new());
+        self.search_input = true;
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn clear_search(&mut self) -> PollResult {
+        self.search = None;
+        self.search_input = false;
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn search_key(&mut self, code: KeyCode) -> PollResult {
+        let query = self.search.as_mut()?;
+
+        match code {
+            KeyCode::Esc => return self.clear_search(),
+            KeyCode::Enter => {
+                self.search_input = false;
+
+                if query.is_empty() {
+                    self.search = None;
+                }
+
+                return Some(vec![]);
+            }
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) => query.push(c),
+            _ => return None,
+        }
+
+        // Jump to the first match from the current node
+        let query = query.clone();
+
+        if !query.is_empty() && !self.active_tree_mut().search(&query, true, true) {
+            self.status = Some("No match".into());
+        }
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn search_next(&mut self, forward: bool) -> PollResult {
+        let query = self.search.clone()?;
+
+        if !self.active_tree_mut().search(&query, forward, false) {
+            self.status = Some("No match".into());
+        }
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn export_json(&mut self) -> PollResult {
+        let path = export_file_name("tree", "json");
+
+        self.status = Some(
+            match self
+                .active_tree()
+                .export_json(&path, &self.stats[self.stat])
+            {
+                Ok(()) => format!("Exported to {}", path.display()),
+                Err(e) => format!("Export failed: {}", e),
+            },
+        );
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn export_visible(&mut self) -> PollResult {
+        let path = export_file_name("tree", "csv");
+
+        self.status = Some(
+            match self
+                .active_tree()
+                .export_visible_csv(&path, &self.stats[self.stat])
+            {
+                Ok(()) => format!("Exported to {}", path.display()),
+                Err(e) => format!("Export failed: {}", e),
+            },
+        );
+
+        Some(vec![])
+    }
+
+    /// Returns the path of the selected cgroup, using the parent of <self> nodes
+    fn selected_path(&self) -> Option<PathBuf> {
+        let mut cgroup = self.active_tree().cgroup()?.path().clone();
+
+        if cgroup.ends_with("<self>") {
+            cgroup.pop();
+        }
+
+        Some(cgroup)
+    }
+
+    #[must_use]
+    fn events_detail(&mut self) -> PollResult {
+        Some(vec![
+            Action::EventsCGroup(self.selected_path()?),
+            Action::Scene(AppScene::CGroupEvents),
+        ])
+    }
+
+    #[must_use]
+    fn watch_cgroup(&mut self) -> PollResult {
+        Some(vec![
+            Action::WatchCGroup(self.selected_path()?),
+            Action::Scene(AppScene::CGroupWatch),
+        ])
+    }
+
+    #[must_use]
+    fn graph_cgroup(&mut self) -> PollResult {
+        Some(vec![
+            Action::GraphCGroup(self.selected_path()?),
+            Action::Scene(AppScene::CGroupGraph),
+        ])
+    }
+
+    #[must_use]
+    fn request_freeze(&mut self) -> PollResult {
+        let cgroup = self.selected_path()?;
+
+        // Freezing our own cgroup would hang the display
+        if self
+            .own_cgroup
+            .as_ref()
+            .is_some_and(|own| own.starts_with(&cgroup))
+        {
+            self.status = Some("Can't freeze a cgroup containing this process".into());
+            return Some(vec![]);
+        }
+
+        match cgroup_frozen(self.cgroup2fs, &cgroup) {
+            Ok(frozen) => self.pending_freeze = Some((cgroup, !frozen)),
+            Err(e) => self.status = Some(format!("Unable to read frozen state: {}", e)),
+        }
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn confirm_freeze_key(&mut self, code: KeyCode) -> PollResult {
+        let (cgroup, freeze) = self.pending_freeze.take()?;
+
+        if code != KeyCode::Char('y') {
+            return Some(vec![]);
+        }
+
+        let desc = if freeze { "freeze" } else { "thaw" };
+
+        match set_cgroup_frozen(self.cgroup2fs, &cgroup, freeze) {
+            Ok(()) => {
+                // Freezing completes asynchronously so read the state back to report it
+                let path = cgroup.to_string_lossy();
+
+                self.status = Some(match cgroup_frozen(self.cgroup2fs, &cgroup) {
+                    Ok(true) if freeze => format!("/{} frozen", path),
+                    Ok(false) if !freeze => format!("/{} thawed", path),
+                    Ok(_) => format!("/{} {} pending", path, desc),
+                    Err(e) => format!("Unable to read the state of /{}: {}", path, e),
+                });
+
+                Some(vec![Action::Reload])
+            }
+            Err(e) => {
+                self.status = Some(format!("Unable to {}: {}", desc, e));
+                Some(vec![])
+            }
+        }
+    }
+
+    #[must_use]
+    fn jump_own_cgroup(&mut self) -> PollResult {
+        let found = match self.own_cgroup.clone() {
+            Some(own) => self.active_tree_mut().select_path(&own),
+            None => false,
         };
 
-        Some(vec![Action::Stat(new_stat), Action::Reload])
+        if !found {
+            self.status = Some("The cgroup containing this process is not shown".into());
+        }
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn copy_path(&mut self) -> PollResult {
+        let mut path = self.cgroup2fs.to_path_buf();
+        path.extend(&self.selected_path()?);
+
+        let path = path.to_string_lossy();
+
+        self.status = Some(match copy_to_clipboard(&path) {
+            Ok(method) => format!("Copied {} to the clipboard using {}", path, method),
+            Err(e) => format!("Unable to copy to the clipboard: {}", e),
+        });
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn open_details(&mut self) -> PollResult {
+        let mut details = CGroupDetailsPopup::new(self.selected_path()?);
+        details.reload(self.cgroup2fs);
+
+        self.details = Some(details);
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn details_key(&mut self, code: KeyCode) -> PollResult {
+        match code {
+            KeyCode::Esc | KeyCode::Enter => {
+                self.details = None;
+                Some(vec![])
+            }
+            code => match self.options.keymap.action(code, &KEY_ACTIONS) {
+                Some(KeyAction::Quit) => {
+                    self.details = None;
+                    Some(vec![])
+                }
+                Some(KeyAction::Reload) => Some(vec![Action::Reload]),
+                _ => None,
+            },
+        }
+    }
+
+    #[must_use]
+    fn edit_note(&mut self) -> PollResult {
+        let cgroup = self.selected_path()?;
+        let note = self.notes.get(&cgroup).unwrap_or_default().to_string();
+
+        self.note_input = Some((cgroup, note));
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn note_key(&mut self, code: KeyCode) -> PollResult {
+        let (_, note) = self.note_input.as_mut()?;
+
+        match code {
+            KeyCode::Esc => self.note_input = None,
+            KeyCode::Enter => {
+                let (cgroup, note) = self.note_input.take()?;
+
+                if let Err(e) = self.notes.set(cgroup, &note) {
+                    self.status = Some(format!("Unable to save notes: {}", e));
+                }
+
+                return Some(vec![Action::Reload]);
+            }
+            KeyCode::Backspace => {
+                note.pop();
+            }
+            KeyCode::Char(c) => note.push(c),
+            _ => return None,
+        }
+
+        Some(vec![])
     }
 
     #[must_use]
     fn procs(&mut self, threads: bool, include_children: bool) -> PollResult {
-        self.tree.cgroup().map(|cgroup| {
+        self.active_tree().cgroup().map(|cgroup| {
             vec![
                 Action::ProcCGroup(cgroup.path().clone()),
                 Action::ProcMode(threads, include_children),
@@ -105,29 +711,199 @@ impl<'a> CGroupTreeScene<'a> {
             ]
         })
     }
+
+    /// Draws the message shown when the cgroup2 filesystem can't be read
+    fn draw_unavailable(&self, terminal: &mut TermType, error: &str) -> Result<(), io::Error> {
+        let keymap = &self.options.keymap;
+
+        terminal.draw(|f| {
+            let block = Block::default()
+                .title(labelled_title(self.options.label.as_deref(), "CGroup Tree"))
+                .borders(Borders::ALL);
+
+            let text = vec![
+                Line::from("cgroup2 filesystem unavailable"),
+                Line::from(""),
+                Line::from(error.to_string()),
+                Line::from(""),
+                Line::from(format!(
+                    "Refreshes are paused. Press {} to retry or {} to quit.",
+                    keymap.key(KeyAction::Reload),
+                    keymap.key(KeyAction::Quit)
+                )),
+            ];
+
+            f.render_widget(Paragraph::new(text).block(block), f.size());
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Returns false if meminfo in the proc filesystem shows no swap space
+fn swap_configured(proc_root: Option<&Path>) -> bool {
+    proc_root.is_none_or(|proc_root| {
+        !matches!(
+            get_file_processor("meminfo/=/1/SwapTotal:/2")
+                .unwrap()
+                .get_stat(proc_root),
+            Ok(0)
+        )
+    })
+}
+
+/// Builds a legend of the magnitudes indicated by each value colour
+fn colour_legend() -> Line<'static> {
+    let powers = colour_powers();
+    let mut spans = vec![Span::from(" ")];
+
+    for (i, colour) in theme().colours.iter().enumerate() {
+        let from = if i == 0 { 0 } else { powers[i - 1] };
+
+        let desc = match powers.get(i) {
+            // Skip colours with an empty range
+            Some(&to) if to <= from => continue,
+            Some(&to) if from == 0 => format!("< {}", POWERS[to]),
+            Some(&to) => format!("{} - {}", POWERS[from], POWERS[to]),
+            None => format!(">= {}", POWERS[from]),
+        };
+
+        spans.push(Span::styled(
+            format!("■ {} ", desc),
+            Style::default().fg(*colour),
+        ));
+    }
+
+    Line::from(spans)
 }
 
 impl<'a> Scene for CGroupTreeScene<'a> {
     fn reload(&mut self) {
-        // Build the tree
-        self.tree.build_tree(self.cgroup2fs, self.stat, self.sort);
+        // Don't touch the filesystem again once it has gone until a reload is requested
+        if self.unavailable.is_none() || self.retry {
+            self.retry = false;
+
+            // Check the root of the displayed hierarchy can still be read
+            let mut path = self.cgroup2fs.to_path_buf();
+            path.extend(&self.options.root);
+            path.push("cgroup.procs");
+
+            self.unavailable = fs::metadata(&path)
+                .err()
+                .map(|e| format!("{}: {}", path.display(), e));
+        }
+
+        if self.unavailable.is_some() {
+            self.next_refresh = Instant::now().checked_add(self.options.interval).unwrap();
+            return;
+        }
+
+        let params = LoadParams {
+            roots: std::iter::once(&self.tree)
+                .chain(self.split.as_ref())
+                .map(|tree| tree.root().clone())
+                .collect(),
+            stat: self.stat,
+            sort: self.sort,
+            options: self.load_options,
+        };
+
+        // Abandon a load started before the view changed
+        if self
+            .loading
+            .as_ref()
+            .is_some_and(|load| load.params != params)
+        {
+            self.loading = None;
+        }
+
+        let load = self.loading.get_or_insert_with(|| {
+            PendingLoad::start(self.cgroup2fs, self.stats[self.stat], params)
+        });
+
+        // Wait briefly so quick loads don't flash the loading indicator. Wait for the load to
+        // finish when paused as there are no further refreshes to pick it up
+        let result = if self.paused {
+            load.receiver.recv().map_err(RecvTimeoutError::from)
+        } else {
+            load.receiver.recv_timeout(LOAD_WAIT)
+        };
+
+        let start = load.start;
+
+        let trees = match result {
+            Ok(Ok(trees)) => {
+                self.loading = None;
+                trees
+            }
+            Ok(Err(e)) => {
+                self.loading = None;
+                self.status = Some(format!("Unable to load cgroups: {}", e));
+                self.next_refresh = Instant::now().checked_add(self.options.interval).unwrap();
+                return;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                // Check again shortly, advancing the loading indicator
+                self.spinner += 1;
+                self.next_refresh = Instant::now().checked_add(SPINNER_INTERVAL).unwrap();
+                return;
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                // The load thread panicked. Try again at the next refresh
+                self.loading = None;
+                self.next_refresh = Instant::now().checked_add(self.options.interval).unwrap();
+                return;
+            }
+        };
+
+        // Build the trees
+        for (tree, cgroups) in std::iter::once(&mut self.tree)
+            .chain(self.split.as_mut())
+            .zip(trees)
+        {
+            tree.set_unescape(self.unescape);
+            tree.set_units(self.units);
+            tree.set_reference(self.reference.clone());
+            tree.set_hide_empty(self.hide_empty);
+            tree.build_tree(
+                cgroups,
+                &self.stats[self.stat],
+                self.load_options,
+                self.growth,
+                &self.notes,
+            );
+        }
+
+        // Refresh the details popup
+        if let Some(details) = &mut self.details {
+            details.reload(self.cgroup2fs);
+        }
+
         self.loads += 1;
+        self.load_time = start.elapsed();
 
         // Calculate next refresh time
-        self.next_refresh = Instant::now().checked_add(Duration::from_secs(5)).unwrap();
+        self.next_refresh = Instant::now().checked_add(self.options.interval).unwrap();
     }
 
     /// Draws the cgroup tree scene
     fn draw(&mut self, terminal: &mut TermType) -> Result<(), io::Error> {
         self.draws += 1;
 
+        if let Some(error) = &self.unavailable {
+            return self.draw_unavailable(terminal, error);
+        }
+
         // Build block title
-        let qty_desc = match STATS[self.stat].stat_type() {
-            StatType::MemQtyCumul => "Memory Usage",
-            StatType::Qty => "Count",
+        let qty_desc = match self.stats[self.stat].format() {
+            StatFormat::Memory => "Memory Usage",
+            StatFormat::Duration => "Usage",
+            StatFormat::Count => "Count",
+            StatFormat::Percent => "Percentage",
         };
 
         let sort_desc = match self.sort {
+            _ if self.growth => "Growth Since Start",
             CGroupSortOrder::NameAsc => "Name Ascending",
             CGroupSortOrder::NameDsc => "Name Descending",
             CGroupSortOrder::StatAsc => "Size Ascending",
@@ -135,27 +911,176 @@ impl<'a> Scene for CGroupTreeScene<'a> {
         };
 
         let mut title = format!(
-            "CGroup {} {} by {} (press 'h' for help)",
-            STATS[self.stat].short_desc(),
+            "CGroup {} {} by {} (press '{}' for help)",
+            self.stats[self.stat].short_desc(),
             qty_desc,
             sort_desc,
+            self.options.keymap.key(KeyAction::Help),
         );
 
-        if self.debug {
+        if !self.options.root.as_os_str().is_empty() {
+            title += &format!(" (under /{})", self.options.root.to_string_lossy());
+        }
+
+        if self.load_options.populated_only {
+            title += " (with processes only)";
+        }
+
+        if self.hide_empty {
+            title += " (hiding empty)";
+        }
+
+        // Swap statistics are always zero without system swap
+        if !self.swap_configured
+            && self.stats[self.stat]
+                .def()
+                .is_some_and(|def| def.contains("swap"))
+        {
+            title += " (no swap configured)";
+        }
+
+        if let Some(reference) = &self.reference {
+            title += &format!(" (relative to /{}", reference.to_string_lossy());
+
+            match self.active_tree().reference_value() {
+                Some(0) => title += ", reference is zero)",
+                Some(_) => title += ")",
+                None => title += ", reference unavailable)",
+            }
+        }
+
+        if let Some((created, removed)) = self.active_tree().churn() {
+            if created > 0 || removed > 0 {
+                title += &format!(" ({} created, {} removed)", created, removed);
+            }
+        }
+
+        if self.paused {
+            title += " (paused)";
+        }
+
+        if self.loading.is_some() {
+            title += &format!(" (loading {})", SPINNER[self.spinner % SPINNER.len()]);
+        }
+
+        if self.options.debug {
             title += &format!(
-                " ({} loads, {} draws, {:?})",
+                " ({} loads, {} draws, {:?} load, {:?})",
                 self.loads,
                 self.draws,
-                self.tree.selected()
+                self.load_time,
+                self.active_tree().selected()
+            );
+        }
+
+        if let Some(warning) = slow_reload_warning(self.load_time, self.options.interval) {
+            title += warning;
+        }
+
+        if let Some(status) = &self.status {
+            title += &format!(" - {}", status);
+        }
+
+        if let Some((cgroup, freeze)) = &self.pending_freeze {
+            title += &format!(
+                " - {} /{}? (y/n)",
+                if *freeze { "Freeze" } else { "Thaw" },
+                cgroup.to_string_lossy()
             );
         }
 
+        if let Some((cgroup, note)) = &self.note_input {
+            title += &format!(" - Note for /{}: {}_", cgroup.to_string_lossy(), note);
+        }
+
+        if let Some(query) = &self.search {
+            if self.search_input {
+                title += &format!(" - Search: {}_", query);
+            } else {
+                title += &format!(" - Search: {} (n / N for next / previous)", query);
+            }
+        }
+
+        // Build the selected value status line
+        let selected_desc = self
+            .active_tree()
+            .cgroup()
+            .filter(|cg| cg.error().is_none())
+            .map(|cg| {
+                format!(
+                    " {} ",
+                    self.stats[self.stat]
+                        .format()
+                        .format_exact(cg.stat(), self.units)
+                )
+            });
+
         terminal.draw(|f| {
             // Create the block
-            let block = Block::default().title(title).borders(Borders::ALL);
+            let mut block = Block::default()
+                .title(labelled_title(self.options.label.as_deref(), &title))
+                .borders(Borders::ALL);
+
+            if self.legend {
+                block = block.title(
+                    Title::from(colour_legend())
+                        .position(Position::Bottom)
+                        .alignment(Alignment::Right),
+                );
+            }
+
+            match &mut self.split {
+                None => {
+                    if let Some(desc) = selected_desc {
+                        block = block.title(Title::from(desc).position(Position::Bottom));
+                    }
+
+                    // Create the tree
+                    self.tree.render(f, f.size(), block);
+                }
+                Some(split) => {
+                    // Split the frame between the full tree and the focused subtree
+                    let chunks = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                        .split(f.size());
+
+                    let mut split_block = Block::default()
+                        .title(labelled_title(
+                            self.options.label.as_deref(),
+                            &format!(
+                                "Focused on /{} (Tab to switch, Esc to close)",
+                                split.root().to_string_lossy()
+                            ),
+                        ))
+                        .borders(Borders::ALL);
+
+                    // Highlight the active pane and show its selected value
+                    let highlight = |block: Block<'a>| {
+                        let block = block.border_style(Style::default().fg(Color::LightYellow));
+
+                        match selected_desc {
+                            Some(desc) => block.title(Title::from(desc).position(Position::Bottom)),
+                            None => block,
+                        }
+                    };
+
+                    if self.split_active {
+                        split_block = highlight(split_block);
+                    } else {
+                        block = highlight(block);
+                    }
+
+                    // Create the trees
+                    self.tree.render(f, chunks[0], block);
+                    split.render(f, chunks[1], split_block);
+                }
+            }
 
-            // Create the tree
-            self.tree.render(f, block);
+            // Draw the details popup over the tree
+            if let Some(details) = &self.details {
+                details.render(f, self.units);
+            }
         })?;
 
         Ok(())
@@ -168,29 +1093,161 @@ impl<'a> Scene for CGroupTreeScene<'a> {
 
     /// Key event
     fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
-        match key_event.code {
-            KeyCode::Char('q') | KeyCode::Esc => Some(vec![Action::Exit]),
-            KeyCode::Left => self.tree.left(),
-            KeyCode::Right => self.tree.right(),
-            KeyCode::Down => self.tree.down(),
-            KeyCode::Up => self.tree.up(),
-            KeyCode::PageDown => self.tree.pg_down(),
-            KeyCode::PageUp => self.tree.pg_up(),
-            KeyCode::Home => self.tree.first(),
-            KeyCode::End => self.tree.last(),
-            KeyCode::Char('c') => self.tree.close_all(),
-            KeyCode::Char('r') => Some(vec![Action::Reload]),
-            KeyCode::Char('n') => self.sort_name(),
-            KeyCode::Char('s') => self.sort_stat(),
-            KeyCode::Char('p') => self.procs(false, false),
-            KeyCode::Char('t') => self.procs(true, false),
-            KeyCode::Char('P') => self.procs(false, true),
-            KeyCode::Char('T') => self.procs(true, true),
-            KeyCode::Char('z') => Some(vec![Action::Scene(AppScene::StatChoose)]),
-            KeyCode::Char('[') => self.next_stat(false),
-            KeyCode::Char(']') => self.next_stat(true),
-            KeyCode::Char('h') => Some(vec![Action::Scene(AppScene::CgroupTreeHelp)]),
-            _ => None,
+        // Clear any status message
+        let cleared = self.status.take().is_some();
+
+        // Only retrying or quitting is possible while the filesystem is unavailable
+        if self.unavailable.is_some() {
+            return match self.options.keymap.action(key_event.code, &KEY_ACTIONS) {
+                Some(KeyAction::Quit) => Some(vec![Action::Exit]),
+                Some(KeyAction::Reload) => {
+                    self.retry = true;
+                    Some(vec![Action::Reload])
+                }
+                _ => None,
+            };
+        }
+
+        // Capture the answer while confirming a freeze
+        if self.pending_freeze.is_some() {
+            return self.confirm_freeze_key(key_event.code);
+        }
+
+        // Capture keys while the details popup is open
+        if self.details.is_some() {
+            return self.details_key(key_event.code);
         }
+
+        // Capture the note text while editing a note
+        if self.note_input.is_some() {
+            return self.note_key(key_event.code);
+        }
+
+        // Capture the query while in search input mode
+        if self.search_input {
+            return self.search_key(key_event.code);
+        }
+
+        // Translate vim style navigation keys
+        let code = vim_nav_code(key_event, |code| {
+            self.options.keymap.action(code, &KEY_ACTIONS).is_some()
+        });
+
+        let result = match code {
+            KeyCode::Esc if self.search.is_some() => self.clear_search(),
+            KeyCode::Esc if self.split.is_some() => self.close_split(),
+            KeyCode::Esc => Some(vec![Action::Exit]),
+            KeyCode::Tab => self.switch_pane(),
+            KeyCode::Enter => self.open_details(),
+            KeyCode::Left => self.active_tree_mut().left(),
+            KeyCode::Right => self.active_tree_mut().right(),
+            KeyCode::Down => self.active_tree_mut().down(),
+            KeyCode::Up => self.active_tree_mut().up(),
+            KeyCode::PageDown => self.active_tree_mut().pg_down(),
+            KeyCode::PageUp => self.active_tree_mut().pg_up(),
+            KeyCode::Home => self.active_tree_mut().first(),
+            KeyCode::End => self.active_tree_mut().last(),
+            KeyCode::Char('n') if self.search.is_some() => self.search_next(true),
+            KeyCode::Char('N') if self.search.is_some() => self.search_next(false),
+            KeyCode::Char(c @ '1'..='9')
+                if self.options.keymap.action(code, &KEY_ACTIONS).is_none() =>
+            {
+                self.active_tree_mut()
+                    .expand_to_depth(c.to_digit(10).unwrap() as usize)
+            }
+            code => match self.options.keymap.action(code, &KEY_ACTIONS) {
+                Some(KeyAction::Search) => self.start_search(),
+                Some(KeyAction::Quit) => Some(vec![Action::Exit]),
+                Some(KeyAction::CollapseAll) => self.active_tree_mut().close_all(),
+                Some(KeyAction::Split) => self.open_split(),
+                Some(KeyAction::Reload) => Some(vec![Action::Reload]),
+                Some(KeyAction::TogglePause) => Some(vec![Action::Pause]),
+                Some(KeyAction::ToggleUnits) => Some(vec![Action::ToggleUnits, Action::Reload]),
+                Some(KeyAction::ToggleRawBytes) => {
+                    Some(vec![Action::ToggleRawBytes, Action::Reload])
+                }
+                Some(KeyAction::Step) if self.paused => Some(vec![Action::Reload]),
+                Some(KeyAction::SortName) => self.sort_name(),
+                Some(KeyAction::SortStat) => self.sort_stat(),
+                Some(KeyAction::ShowProcs) => self.procs(false, false),
+                Some(KeyAction::ShowThreads) => self.procs(true, false),
+                Some(KeyAction::ShowHierProcs) => self.procs(false, true),
+                Some(KeyAction::ShowHierThreads) => self.procs(true, true),
+                Some(KeyAction::EventsDetail) => self.events_detail(),
+                Some(KeyAction::WatchCGroup) => self.watch_cgroup(),
+                Some(KeyAction::GraphCGroup) => self.graph_cgroup(),
+                Some(KeyAction::Freeze) => self.request_freeze(),
+                Some(KeyAction::EditNote) => self.edit_note(),
+                Some(KeyAction::CopyPath) => self.copy_path(),
+                Some(KeyAction::JumpOwnCGroup) => self.jump_own_cgroup(),
+                Some(KeyAction::FlatView) => Some(vec![Action::Scene(AppScene::CGroupFlat)]),
+                Some(KeyAction::Footprint) => Some(vec![Action::Scene(AppScene::CGroupFootprint)]),
+                Some(KeyAction::ToggleOomGroup) => self.toggle_oom_group(),
+                Some(KeyAction::ToggleTaskCounts) => self.toggle_task_counts(),
+                Some(KeyAction::ToggleProcAverage) => self.toggle_proc_average(),
+                Some(KeyAction::ToggleInode) => self.toggle_inode(),
+                Some(KeyAction::ToggleSubtreeControl) => self.toggle_subtree_control(),
+                Some(KeyAction::TogglePopulatedOnly) => self.toggle_populated_only(),
+                Some(KeyAction::ToggleHideEmpty) => self.toggle_hide_empty(),
+                Some(KeyAction::ToggleLegend) => self.toggle_legend(),
+                Some(KeyAction::ToggleGrowth) => self.toggle_growth(),
+                Some(KeyAction::ToggleUnescape) => self.toggle_unescape(),
+                Some(KeyAction::ToggleReclaim) => self.toggle_reclaim(),
+                Some(KeyAction::ToggleState) => self.toggle_state(),
+                Some(KeyAction::ToggleIoRates) => self.toggle_io_rates(),
+                Some(KeyAction::MarkReference) => self.mark_reference(),
+                Some(KeyAction::ChooseStat) => Some(vec![Action::Scene(AppScene::StatChoose)]),
+                Some(KeyAction::PrevStat) => self.next_stat(false),
+                Some(KeyAction::NextStat) => self.next_stat(true),
+                Some(KeyAction::Export) => self.export_visible(),
+                Some(KeyAction::ExportJson) => self.export_json(),
+                Some(KeyAction::Help) => Some(vec![Action::Scene(AppScene::CgroupTreeHelp)]),
+                _ => None,
+            },
+        };
+
+        if result.is_none() && cleared {
+            Some(vec![])
+        } else {
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::app::keymap::KeyMap;
+    use crate::cgroup::fixture::Fixture;
+
+    #[test]
+    fn default_keys_unique() {
+        let keymap = KeyMap::default();
+        let mut keys = HashSet::new();
+
+        for action in KEY_ACTIONS {
+            assert!(keys.insert(keymap.key(action)), "{:?} shares a key", action);
+        }
+    }
+
+    #[test]
+    fn vim_keys_not_bound_by_default() {
+        let keymap = KeyMap::default();
+
+        for c in ['j', 'k', 'g', 'G'] {
+            assert_eq!(keymap.action(KeyCode::Char(c), &KEY_ACTIONS), None);
+        }
+    }
+
+    #[test]
+    fn swap_read_from_proc_root() {
+        let none = Fixture::new(&[("meminfo", "MemTotal: 1000 kB\nSwapTotal: 0 kB\n")]);
+        let some = Fixture::new(&[("meminfo", "MemTotal: 1000 kB\nSwapTotal: 500 kB\n")]);
+
+        assert!(!swap_configured(Some(none.path())));
+        assert!(swap_configured(Some(some.path())));
+        assert!(swap_configured(None));
     }
 }
@@ -1,28 +1,126 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::Block;
 use ratatui::Frame;
 use tui_tree_widget::{flatten, Tree, TreeItem, TreeState};
 
+use crate::app::scenes::render_scrollbar;
 use crate::app::PollResult;
-use crate::cgroup::stats::{StatType, STATS};
-use crate::cgroup::{load_cgroups, CGroup, CGroupSortOrder};
-use crate::formatters::{format_mem_qty, format_qty};
+use crate::cgroup::stats::{Stat, StatFormat, StatType};
+use crate::cgroup::{remove_empty, CGroup, LoadOptions};
+use crate::config::Notes;
+use crate::formatters::{csv_field, format_rate, json_string, theme, unescape_systemd, MemUnits};
+
+/// Values calculated from previous loads shown alongside a node
+struct NodeAnnotations {
+    throttled: bool,
+    growth: Option<i128>,
+    delta: Option<i128>,
+    reclaimed: Option<bool>,
+    io_rates: Option<(usize, usize)>,
+    reference: bool,
+    own: bool,
+    ratio: Option<f64>,
+    note: Option<String>,
+    warning: bool,
+}
 
 #[derive(Default)]
 pub struct CGroupTree<'a> {
     cgroups: Vec<CGroup>,
     items: Vec<TreeItem<'a, usize>>,
     state: TreeState<usize>,
+    high_events: HashMap<PathBuf, usize>,
+    usages: HashMap<PathBuf, usize>,
+    io_bytes: HashMap<PathBuf, (usize, usize)>,
+    io_elapsed: Option<Duration>,
+    last_load: Option<Instant>,
+    baselines: HashMap<String, HashMap<PathBuf, usize>>,
+    growth: bool,
     single_root: bool,
     page_size: u16,
+    root: PathBuf,
+    unescape: bool,
+    units: MemUnits,
+    reference: Option<PathBuf>,
+    reference_value: Option<usize>,
+    paths: HashSet<PathBuf>,
+    paths_key: Option<(PathBuf, bool)>,
+    churn: Option<(usize, usize)>,
+    warn: Option<usize>,
+    previous: HashMap<PathBuf, usize>,
+    previous_stat: Option<String>,
+    own_cgroup: Option<PathBuf>,
+    hide_empty: bool,
 }
 
 impl<'a> CGroupTree<'a> {
-    /// Build tree
-    pub fn build_tree(&mut self, cgroup2fs: &Path, stat: usize, sort: CGroupSortOrder) {
+    /// Sets the cgroup to use as the root of the tree
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.root = root;
+    }
+
+    /// Sets whether systemd escapes in cgroup names are decoded for display
+    pub fn set_unescape(&mut self, unescape: bool) {
+        self.unescape = unescape;
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+    }
+
+    /// Sets the cgroup which values are shown relative to
+    pub fn set_reference(&mut self, reference: Option<PathBuf>) {
+        self.reference = reference;
+    }
+
+    /// Returns the value of the reference cgroup from the last load
+    pub fn reference_value(&self) -> Option<usize> {
+        self.reference_value
+    }
+
+    /// Sets the cgroup containing this process, which is marked in the tree
+    pub fn set_own_cgroup(&mut self, own_cgroup: Option<PathBuf>) {
+        self.own_cgroup = own_cgroup;
+    }
+
+    /// Sets the memory statistic value above which cgroups are highlighted
+    pub fn set_warn(&mut self, warn: Option<usize>) {
+        self.warn = warn;
+    }
+
+    /// Sets whether cgroups without a value for the statistic are hidden
+    pub fn set_hide_empty(&mut self, hide_empty: bool) {
+        self.hide_empty = hide_empty;
+    }
+
+    /// Returns the number of cgroups created and removed since the previous load
+    pub fn churn(&self) -> Option<(usize, usize)> {
+        self.churn
+    }
+
+    /// Returns the cgroup used as the root of the tree
+    pub fn root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    /// Builds the tree from freshly loaded cgroups
+    pub fn build_tree(
+        &mut self,
+        mut cgroups: Vec<CGroup>,
+        stat: &Stat,
+        options: LoadOptions,
+        growth: bool,
+        notes: &Notes,
+    ) {
         // Save currently selected node path
         let old_selected = self.cgroup().map(|cg| cg.path().clone());
 
@@ -38,12 +136,89 @@ impl<'a> CGroupTree<'a> {
         // Close all opened
         self.state.close_all();
 
-        // Load cgroup information
-        let cgroups = load_cgroups(cgroup2fs, stat, sort);
+        // Save the values from the last load of this statistic for the changes since then
+        self.previous.clear();
+
+        if self.previous_stat.as_deref() == Some(stat.short_desc()) {
+            Self::save_baseline(&self.cgroups, &mut self.previous);
+        }
+
+        self.previous_stat = Some(stat.short_desc().to_string());
+
+        // Find the paths of the loaded cgroups
+        let mut paths = HashSet::new();
+        Self::collect_paths(&cgroups, &mut paths);
+
+        // Forget the baselines of cgroups which have gone
+        for baseline in self.baselines.values_mut() {
+            baseline.retain(|path, _| {
+                paths.contains(path)
+                    || (path.ends_with("<self>")
+                        && path.parent().is_some_and(|parent| paths.contains(parent)))
+            });
+        }
+
+        // Record the first seen value of each cgroup for this statistic
+        let baseline = self
+            .baselines
+            .entry(stat.short_desc().to_string())
+            .or_default();
+        Self::save_baseline(&cgroups, baseline);
+
+        // Sort by growth since the first load if required
+        self.growth = growth;
+
+        if growth {
+            Self::sort_by_growth(&mut cgroups, baseline);
+        }
+
+        // Find the value of the reference cgroup
+        self.reference_value = self
+            .reference
+            .as_ref()
+            .and_then(|path| Self::find_path(&cgroups, path))
+            .filter(|cg| cg.error().is_none())
+            .map(|cg| cg.stat());
+
+        // Calculate the time since the last load for rates
+        let now = Instant::now();
+        self.io_elapsed = self.last_load.map(|last| now.duration_since(last));
+        self.last_load = Some(now);
+
+        // Count the cgroups created and removed since the last load. The counts are only
+        // comparable if the same part of the hierarchy was loaded
+        let paths_key = (self.root.clone(), options.populated_only);
+
+        self.churn = (self.paths_key.as_ref() == Some(&paths_key)).then(|| {
+            (
+                paths.difference(&self.paths).count(),
+                self.paths.difference(&paths).count(),
+            )
+        });
+
+        self.paths = paths;
+        self.paths_key = Some(paths_key);
+
+        // Hide cgroups without a value. This is done after counting the churn so hidden
+        // cgroups aren't counted as removed
+        if self.hide_empty {
+            remove_empty(&mut cgroups);
+        }
 
         // Build tree items
         let (select, items) =
-            self.build_tree_level(&cgroups, stat, &old_selected, &old_opened, vec![]);
+            self.build_tree_level(&cgroups, stat, notes, &old_selected, &old_opened, vec![]);
+
+        // Save the memory.high event counts and memory usage for the next load
+        self.high_events.clear();
+        self.usages.clear();
+        self.io_bytes.clear();
+        Self::save_previous(
+            &cgroups,
+            &mut self.high_events,
+            &mut self.usages,
+            &mut self.io_bytes,
+        );
 
         // Save the vectors
         self.cgroups = cgroups;
@@ -54,6 +229,16 @@ impl<'a> CGroupTree<'a> {
             self.state.select(select);
         } else {
             self.state.select(vec![]);
+
+            // The node may have gone, for example a <self> node which is only present for some
+            // statistics. Select the nearest ancestor still in the tree instead
+            if let Some(old_selected) = old_selected {
+                for ancestor in old_selected.ancestors().skip(1) {
+                    if self.select_path(ancestor) {
+                        break;
+                    }
+                }
+            }
         }
 
         // Expand the root node is we're switching to a view with a single root node
@@ -67,10 +252,22 @@ impl<'a> CGroupTree<'a> {
         }
     }
 
+    /// Adds the paths of all cgroups in the hierarchy to the set, skipping <self> nodes
+    fn collect_paths(cgroups: &[CGroup], paths: &mut HashSet<PathBuf>) {
+        for cg in cgroups {
+            if !cg.path().ends_with("<self>") {
+                paths.insert(cg.path().clone());
+            }
+
+            Self::collect_paths(cg.children(), paths);
+        }
+    }
+
     fn build_tree_level(
         &mut self,
         cgroups: &[CGroup],
-        stat: usize,
+        stat: &Stat,
+        notes: &Notes,
         old_selected: &Option<PathBuf>,
         old_opened: &Vec<PathBuf>,
         cur_item: Vec<usize>,
@@ -79,8 +276,85 @@ impl<'a> CGroupTree<'a> {
         let mut tree_items = Vec::new();
 
         for (i, cg) in cgroups.iter().enumerate() {
+            // Has memory.high been exceeded since the last load?
+            let throttled = match (cg.high_events(), self.high_events.get(cg.path())) {
+                (Some(count), Some(old_count)) => count > *old_count,
+                _ => false,
+            };
+
+            // Did reclaim reduce memory usage after hitting memory.high?
+            let reclaimed = match (cg.usage(), self.usages.get(cg.path())) {
+                (Some(usage), Some(old_usage)) if throttled => Some(usage < *old_usage),
+                _ => None,
+            };
+
+            // Calculate the read and write rates since the last load
+            let io_rates = match (cg.io_bytes(), self.io_bytes.get(cg.path()), self.io_elapsed) {
+                (Some((read, write)), Some((old_read, old_write)), Some(elapsed))
+                    if !elapsed.is_zero() =>
+                {
+                    // Clamp negative differences to zero in case the cgroup was recreated
+                    let rate = |new: usize, old: usize| {
+                        (new.saturating_sub(old) as f64 / elapsed.as_secs_f64()) as usize
+                    };
+
+                    Some((rate(read, *old_read), rate(write, *old_write)))
+                }
+                _ => None,
+            };
+
+            // Calculate the growth since the first load
+            let growth = if self.growth {
+                self.baselines
+                    .get(stat.short_desc())
+                    .and_then(|baseline| baseline.get(cg.path()))
+                    .map(|base| cg.stat() as i128 - *base as i128)
+            } else {
+                None
+            };
+
+            // Calculate the change since the last load
+            let delta = self
+                .previous
+                .get(cg.path())
+                .filter(|_| cg.error().is_none())
+                .map(|previous| cg.stat() as i128 - *previous as i128);
+
+            // Calculate the ratio to the reference cgroup, guarding against a zero reference
+            let reference = self.reference.as_ref() == Some(cg.path());
+
+            let own = self.own_cgroup.as_ref() == Some(cg.path());
+
+            let ratio = match self.reference_value {
+                Some(ref_value) if ref_value > 0 && !reference => {
+                    Some(cg.stat() as f64 / ref_value as f64)
+                }
+                _ => None,
+            };
+
+            // Has a memory statistic exceeded the warning threshold?
+            let warning = stat.format() == StatFormat::Memory
+                && self.warn.is_some_and(|warn| cg.stat() > warn);
+
             // Build text for this node
-            let text: Text = Self::cgroup_text(cg, stat);
+            let text: Text = Self::cgroup_text(
+                cg,
+                stat,
+                self.unescape,
+                self.units,
+                NodeAnnotations {
+                    throttled,
+                    growth,
+                    delta,
+                    reclaimed,
+                    io_rates,
+                    reference,
+                    own,
+                    ratio,
+                    note: notes.get(cg.path()).map(String::from),
+                    warning,
+                },
+            );
 
             // Add node to the index vector
             let mut next = cur_item.clone();
@@ -104,7 +378,7 @@ impl<'a> CGroupTree<'a> {
 
             // Process sub nodes
             let (sub_select, sub_nodes) =
-                self.build_tree_level(cg.children(), stat, old_selected, old_opened, next);
+                self.build_tree_level(cg.children(), stat, notes, old_selected, old_opened, next);
 
             if sub_select.is_some() {
                 select = sub_select;
@@ -117,52 +391,360 @@ impl<'a> CGroupTree<'a> {
         (select, tree_items)
     }
 
+    fn save_previous(
+        cgroups: &[CGroup],
+        high_events: &mut HashMap<PathBuf, usize>,
+        usages: &mut HashMap<PathBuf, usize>,
+        io_bytes: &mut HashMap<PathBuf, (usize, usize)>,
+    ) {
+        for cg in cgroups {
+            if let Some(count) = cg.high_events() {
+                high_events.insert(cg.path().clone(), count);
+            }
+
+            if let Some(usage) = cg.usage() {
+                usages.insert(cg.path().clone(), usage);
+            }
+
+            if let Some(bytes) = cg.io_bytes() {
+                io_bytes.insert(cg.path().clone(), bytes);
+            }
+
+            Self::save_previous(cg.children(), high_events, usages, io_bytes);
+        }
+    }
+
+    fn save_baseline(cgroups: &[CGroup], baseline: &mut HashMap<PathBuf, usize>) {
+        for cg in cgroups {
+            if cg.error().is_none() {
+                baseline.entry(cg.path().clone()).or_insert(cg.stat());
+            }
+
+            Self::save_baseline(cg.children(), baseline);
+        }
+    }
+
+    fn sort_by_growth(cgroups: &mut [CGroup], baseline: &HashMap<PathBuf, usize>) {
+        let growth = |cg: &CGroup| match baseline.get(cg.path()) {
+            Some(base) if cg.error().is_none() => cg.stat() as i128 - *base as i128,
+            _ => 0,
+        };
+
+        cgroups.sort_by(|a, b| growth(a).cmp(&growth(b)).reverse());
+
+        for cg in cgroups.iter_mut() {
+            Self::sort_by_growth(cg.children_mut(), baseline);
+        }
+    }
+
     #[must_use]
-    fn cgroup_text(cgroup: &CGroup, stat: usize) -> Text<'a> {
+    fn cgroup_text(
+        cgroup: &CGroup,
+        stat: &Stat,
+        unescape: bool,
+        units: MemUnits,
+        annotations: NodeAnnotations,
+    ) -> Text<'a> {
+        let NodeAnnotations {
+            throttled,
+            growth,
+            delta,
+            reclaimed,
+            io_rates,
+            reference,
+            own,
+            ratio,
+            note,
+            warning,
+        } = annotations;
+
         let filename = cgroup.path().file_name();
 
         // Get path as a string
         let pathstr = match filename {
+            Some(f) if unescape => unescape_systemd(&f.to_string_lossy()),
             Some(f) => f.to_string_lossy().clone().into(),
             None => "/".to_string(),
         };
 
         let path = Span::from(pathstr);
 
+        // Glyph showing whether the cgroup is frozen or contains processes
+        let glyph = cgroup.event_flags().map(|flags| {
+            if flags.frozen {
+                Span::styled("❄ ", Style::default().fg(Color::LightBlue))
+            } else if flags.populated {
+                Span::styled("● ", Style::default().fg(Color::LightGreen))
+            } else {
+                Span::styled("○ ", Style::default().add_modifier(Modifier::DIM))
+            }
+        });
+
+        // Marker for the cgroup containing this process
+        let own_marker = own.then(|| {
+            Span::styled(
+                " ← this process",
+                Style::default()
+                    .fg(Color::LightCyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+        });
+
         Text::from(Line::from(match cgroup.error() {
             Some(msg) => {
-                vec![
-                    Span::raw("         "),
-                    path,
-                    Span::raw(" - "),
-                    Span::styled(msg.clone(), Style::default().fg(Color::Red)),
-                ]
+                let mut spans = vec![Span::raw("         "), glyph.unwrap_or_default(), path];
+
+                spans.extend(own_marker);
+                spans.push(Span::raw(" - "));
+                spans.push(Span::styled(msg.clone(), Style::default().fg(Color::Red)));
+
+                spans
             }
             None => {
-                let span = match STATS[stat].stat_type() {
-                    StatType::MemQtyCumul => format_mem_qty(cgroup.stat()),
-                    StatType::Qty => format_qty(cgroup.stat()),
-                };
-                vec![span, Span::raw(": "), path]
+                let mut span = stat.format().format(cgroup.stat(), units);
+
+                if warning {
+                    span.style = Style::default().fg(Color::White).bg(Color::Red);
+                }
+
+                let mut spans = vec![span];
+
+                if let Some(growth) = growth {
+                    let amount = usize::try_from(growth.unsigned_abs()).unwrap_or(usize::MAX);
+
+                    let growth_span = stat.format().format(amount, units);
+
+                    let sign = match growth.signum() {
+                        1 => '+',
+                        -1 => '-',
+                        _ => ' ',
+                    };
+
+                    spans.push(Span::styled(
+                        format!(" {}{}", sign, growth_span.content),
+                        growth_span.style,
+                    ));
+                }
+
+                // Show the change since the last load, increases in red
+                if let Some(delta) = delta.filter(|delta| *delta != 0) {
+                    let amount = usize::try_from(delta.unsigned_abs()).unwrap_or(usize::MAX);
+
+                    let (sign, colour) = if delta > 0 {
+                        ('+', Color::LightRed)
+                    } else {
+                        ('-', Color::LightGreen)
+                    };
+
+                    spans.push(Span::styled(
+                        format!(
+                            " {}{}",
+                            sign,
+                            stat.format().format(amount, units).content.trim()
+                        ),
+                        Style::default().fg(colour),
+                    ));
+                }
+
+                spans.push(Span::raw(": "));
+                spans.extend(glyph);
+                spans.push(path);
+
+                spans.extend(own_marker.clone());
+
+                if reference {
+                    spans.push(Span::styled(
+                        " [reference]",
+                        Style::default().fg(Color::LightYellow),
+                    ));
+                } else if let Some(ratio) = ratio {
+                    spans.push(Span::styled(
+                        format!(" ({:.1}x reference)", ratio),
+                        Style::default().fg(Color::LightYellow),
+                    ));
+                }
+
+                if let Some((procs, threads)) = cgroup.task_counts() {
+                    let text = if procs > 0 {
+                        format!(
+                            " ({} threads / {} procs = {:.1})",
+                            threads,
+                            procs,
+                            threads as f64 / procs as f64
+                        )
+                    } else {
+                        format!(" ({} threads / {} procs)", threads, procs)
+                    };
+
+                    spans.push(Span::styled(
+                        text,
+                        Style::default().add_modifier(Modifier::DIM),
+                    ));
+                }
+
+                // Show the average per process, except for percentages which can't be combined
+                if let Some(count) = cgroup
+                    .proc_count()
+                    .filter(|_| stat.stat_type() != StatType::Pct)
+                {
+                    let text = if count > 0 {
+                        format!(
+                            " ({} procs, {} / proc)",
+                            count,
+                            stat.format()
+                                .format(cgroup.stat() / count, units)
+                                .content
+                                .trim()
+                        )
+                    } else {
+                        " (0 procs)".into()
+                    };
+
+                    spans.push(Span::styled(text, Style::default().fg(Color::LightYellow)));
+                }
+
+                if let Some((read, write)) = io_rates {
+                    spans.push(Span::styled(
+                        format!(
+                            " (read {} write {})",
+                            format_rate(read, units).content,
+                            format_rate(write, units).content
+                        ),
+                        Style::default().fg(Color::LightBlue),
+                    ));
+                }
+
+                if let Some(inode) = cgroup.inode() {
+                    spans.push(Span::styled(
+                        format!(" (inode {})", inode),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ));
+                }
+
+                if let Some(controllers) = cgroup.subtree_control() {
+                    if !controllers.is_empty() {
+                        spans.push(Span::styled(
+                            format!(" [subtree: {}]", controllers.join(" ")),
+                            Style::default().fg(Color::LightCyan),
+                        ));
+                    }
+                }
+
+                if cgroup.oom_group() {
+                    spans.push(Span::styled(
+                        " [oom-group]",
+                        Style::default().fg(Color::LightCyan),
+                    ));
+                }
+
+                if throttled {
+                    spans.push(Span::styled(
+                        " [throttled]",
+                        Style::default().fg(Color::LightMagenta),
+                    ));
+                }
+
+                match reclaimed {
+                    Some(true) => spans.push(Span::styled(
+                        " [reclaiming]",
+                        Style::default().fg(Color::LightGreen),
+                    )),
+                    Some(false) => spans.push(Span::styled(
+                        " [reclaim-inefficient]",
+                        Style::default().fg(Color::LightRed),
+                    )),
+                    None => (),
+                }
+
+                if let Some(note) = note {
+                    spans.push(Span::styled(
+                        format!(" # {}", note),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ));
+                }
+
+                spans
             }
         }))
     }
 
-    pub fn render(&mut self, frame: &mut Frame, block: Block) {
-        // Get the size of the frame
-        let size = frame.size();
-
+    pub fn render(&mut self, frame: &mut Frame, size: Rect, block: Block) {
         // Calculate number of rows in a page
-        self.page_size = std::cmp::max(2, block.inner(size).height) - 1;
+        let inner_height = block.inner(size).height;
+        self.page_size = std::cmp::max(2, inner_height) - 1;
 
         // Create the tree
         let tree = Tree::new(self.items.clone())
             .unwrap()
             .block(block)
-            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            .highlight_style(theme().highlight);
 
         // Draw the tree
         frame.render_stateful_widget(tree, size, &mut self.state);
+
+        // Draw the scrollbar
+        let visible = flatten(&self.state.get_all_opened(), &self.items);
+        let selected = self.selected();
+
+        render_scrollbar(
+            frame,
+            size,
+            visible.len(),
+            inner_height as usize,
+            visible
+                .iter()
+                .position(|v| v.identifier == selected)
+                .unwrap_or(0),
+        );
+    }
+
+    /// Exports the visible tree nodes in the displayed order to a CSV file
+    pub fn export_visible_csv(&self, path: &Path, stat: &Stat) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+
+        writeln!(file, "Depth,Path,{}", csv_field(stat.short_desc()))?;
+
+        for item in flatten(&self.state.get_all_opened(), &self.items) {
+            if let Some(cg) = self.cgroup_from_selected(item.identifier.clone()) {
+                let value = match cg.error() {
+                    Some(msg) => msg.clone(),
+                    None => cg.stat().to_string(),
+                };
+
+                writeln!(
+                    file,
+                    "{},{},{}",
+                    item.depth(),
+                    csv_field(&format!("/{}", cg.path().to_string_lossy())),
+                    csv_field(&value)
+                )?;
+            }
+        }
+
+        file.flush()
+    }
+
+    /// Exports the whole loaded tree to a JSON file
+    pub fn export_json(&self, path: &Path, stat: &Stat) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+
+        write!(
+            file,
+            "{{\"stat\":{},\"cgroups\":[",
+            json_string(stat.short_desc())
+        )?;
+
+        for (i, cg) in self.cgroups.iter().enumerate() {
+            if i > 0 {
+                write!(file, ",")?;
+            }
+
+            cg.write_json(&mut file)?;
+        }
+
+        writeln!(file, "]}}")?;
+
+        file.flush()
     }
 
     fn move_by(&mut self, amount: isize, no_pos: isize) -> PollResult {
@@ -244,6 +826,49 @@ impl<'a> CGroupTree<'a> {
         Some(vec![])
     }
 
+    /// Expands the tree to show the given number of levels, collapsing deeper nodes. Levels are
+    /// counted below the root node if it's the only top level node
+    #[must_use]
+    pub fn expand_to_depth(&mut self, depth: usize) -> PollResult {
+        let levels = if self.single_root { depth + 1 } else { depth };
+
+        self.state.close_all();
+        Self::open_level(&mut self.state, &self.cgroups, levels, vec![]);
+
+        // Move the selection to the nearest ancestor still visible
+        let mut selected = self.selected();
+
+        if selected.len() > levels {
+            selected.truncate(levels);
+            self.state.select(selected);
+        }
+
+        Some(vec![])
+    }
+
+    fn open_level(
+        state: &mut TreeState<usize>,
+        cgroups: &[CGroup],
+        levels: usize,
+        cur_item: Vec<usize>,
+    ) {
+        if cur_item.len() + 1 >= levels {
+            return;
+        }
+
+        for (i, cg) in cgroups.iter().enumerate() {
+            if cg.children().is_empty() {
+                continue;
+            }
+
+            let mut next = cur_item.clone();
+            next.push(i);
+
+            state.open(next.clone());
+            Self::open_level(state, cg.children(), levels, next);
+        }
+    }
+
     #[must_use]
     pub fn selected(&self) -> Vec<usize> {
         self.state.selected()
@@ -254,6 +879,121 @@ impl<'a> CGroupTree<'a> {
         self.cgroup_from_selected(self.selected())
     }
 
+    /// Finds the cgroup with the largest value, excluding the root and <self> nodes
+    pub fn top_cgroup(&self) -> Option<&CGroup> {
+        Self::find_top(&self.cgroups, None)
+    }
+
+    /// Selects the next or previous node whose name contains the query, expanding its ancestors.
+    /// Returns false if there are no matches
+    pub fn search(&mut self, query: &str, forward: bool, include_current: bool) -> bool {
+        let query = query.to_lowercase();
+
+        // Find the identifiers of all matching nodes in tree order
+        let mut matches = Vec::new();
+        Self::search_level(&self.cgroups, &query, vec![], &mut matches);
+
+        let current = self.selected();
+
+        let found = if forward {
+            matches
+                .iter()
+                .find(|m| **m > current || (include_current && **m == current))
+                .or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|m| **m < current)
+                .or(matches.last())
+        };
+
+        match found {
+            Some(identifier) => {
+                // Expand all ancestors
+                for len in 1..identifier.len() {
+                    self.state.open(identifier[..len].to_vec());
+                }
+
+                self.state.select(identifier.clone());
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn search_level(
+        cgroups: &[CGroup],
+        query: &str,
+        cur_item: Vec<usize>,
+        matches: &mut Vec<Vec<usize>>,
+    ) {
+        for (i, cg) in cgroups.iter().enumerate() {
+            let mut next = cur_item.clone();
+            next.push(i);
+
+            if let Some(name) = cg.path().file_name() {
+                if name.to_string_lossy().to_lowercase().contains(query) {
+                    matches.push(next.clone());
+                }
+            }
+
+            Self::search_level(cg.children(), query, next, matches);
+        }
+    }
+
+    /// Selects the node with the given path, expanding its ancestors. Returns false if the path
+    /// is not in the tree
+    pub fn select_path(&mut self, path: &Path) -> bool {
+        let mut level = &self.cgroups;
+        let mut identifier = Vec::new();
+
+        while let Some(i) = level.iter().position(|cg| path.starts_with(cg.path())) {
+            identifier.push(i);
+
+            if level[i].path() == path {
+                self.state.select(identifier);
+                return true;
+            }
+
+            self.state.open(identifier.clone());
+            level = level[i].children();
+        }
+
+        false
+    }
+
+    /// Finds the cgroup with the given path
+    fn find_path<'b>(cgroups: &'b [CGroup], path: &Path) -> Option<&'b CGroup> {
+        let mut level = cgroups;
+
+        while let Some(cg) = level.iter().find(|cg| path.starts_with(cg.path())) {
+            if cg.path() == path {
+                return Some(cg);
+            }
+
+            level = cg.children();
+        }
+
+        None
+    }
+
+    fn find_top<'b>(cgroups: &'b [CGroup], mut top: Option<&'b CGroup>) -> Option<&'b CGroup> {
+        for cg in cgroups {
+            if !cg.path().as_os_str().is_empty()
+                && !cg.path().ends_with("<self>")
+                && cg.error().is_none()
+                && top.is_none_or(|top| cg.stat() > top.stat())
+            {
+                top = Some(cg);
+            }
+
+            top = Self::find_top(cg.children(), top);
+        }
+
+        top
+    }
+
     #[must_use]
     fn cgroup_from_selected(&self, selected: Vec<usize>) -> Option<&CGroup> {
         let (cgroup, _) = selected
@@ -265,3 +1005,106 @@ impl<'a> CGroupTree<'a> {
         cgroup
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::cgroup::fixture::Fixture;
+    use crate::cgroup::stats::STATS;
+    use crate::cgroup::{load_cgroups, CGroupSortOrder};
+
+    /// Loads the fixture with a statistic and builds the tree from it
+    fn build(tree: &mut CGroupTree, fixture: &Fixture, short_desc: &str, growth: bool) {
+        let stat = STATS.iter().find(|s| s.short_desc() == short_desc).unwrap();
+
+        let cgroups = load_cgroups(
+            fixture.path(),
+            Path::new(""),
+            stat,
+            CGroupSortOrder::NameAsc,
+            LoadOptions::default(),
+        )
+        .unwrap();
+
+        tree.build_tree(
+            cgroups,
+            stat,
+            LoadOptions::default(),
+            growth,
+            &Notes::default(),
+        );
+    }
+
+    fn selected(tree: &CGroupTree) -> Option<PathBuf> {
+        tree.cgroup().map(|cg| cg.path().clone())
+    }
+
+    #[test]
+    fn reselects_ancestor_of_removed_self_node() {
+        let fixture = Fixture::new(&[
+            ("memory.current", "2000"),
+            ("memory.max", "max"),
+            ("a/memory.current", "1000"),
+            ("a/memory.max", "max"),
+            ("a/b/memory.current", "400"),
+            ("a/b/memory.max", "max"),
+        ]);
+
+        let mut tree = CGroupTree::default();
+
+        // Current Total has a <self> node for the memory not used by children
+        build(&mut tree, &fixture, "Current Total", false);
+        assert!(tree.select_path(Path::new("a/<self>")));
+        assert_eq!(selected(&tree), Some(PathBuf::from("a/<self>")));
+
+        // Max Limit has no <self> nodes so the parent is selected instead
+        build(&mut tree, &fixture, "Max Limit", false);
+        assert_eq!(selected(&tree), Some(PathBuf::from("a")));
+
+        // A node still present stays selected
+        assert!(tree.select_path(Path::new("a/b")));
+        build(&mut tree, &fixture, "Current Total", false);
+        assert_eq!(selected(&tree), Some(PathBuf::from("a/b")));
+    }
+
+    #[test]
+    fn baselines_from_first_load() {
+        let fixture = Fixture::new(&[
+            ("memory.current", "300"),
+            ("a/memory.current", "100"),
+            ("b/memory.current", "100"),
+        ]);
+
+        let mut tree = CGroupTree::default();
+
+        // The baseline is recorded on the first load, even with growth not shown
+        build(&mut tree, &fixture, "Current Total", false);
+        assert_eq!(
+            tree.baselines["Current Total"].get(Path::new("a")),
+            Some(&100)
+        );
+
+        fs::write(fixture.path().join("a/memory.current"), "150").unwrap();
+
+        build(&mut tree, &fixture, "Current Total", true);
+        build(&mut tree, &fixture, "Current Total", false);
+        build(&mut tree, &fixture, "Current Total", true);
+
+        let baseline = &tree.baselines["Current Total"];
+        assert_eq!(baseline.get(Path::new("a")), Some(&100));
+        assert!(baseline.contains_key(Path::new("b")));
+        assert!(baseline.contains_key(Path::new("<self>")));
+
+        // Removed cgroups are forgotten
+        fs::remove_file(fixture.path().join("b/memory.current")).unwrap();
+        fs::remove_dir(fixture.path().join("b")).unwrap();
+
+        build(&mut tree, &fixture, "Current Total", false);
+        let baseline = &tree.baselines["Current Total"];
+        assert!(!baseline.contains_key(Path::new("b")));
+        assert_eq!(baseline.get(Path::new("a")), Some(&100));
+        assert!(baseline.contains_key(Path::new("<self>")));
+    }
+}
@@ -1,7 +1,10 @@
 use super::help::HelpScene;
+use crate::app::keymap::KeyAction;
+use crate::app::AppOptions;
 
-pub fn build_cgroup_tree_help_scene<'a>() -> HelpScene<'a> {
-    let mut help = HelpScene::new();
+pub fn build_cgroup_tree_help_scene(options: &AppOptions) -> HelpScene<'_> {
+    let keymap = &options.keymap;
+    let mut help = HelpScene::new(options.label.as_deref(), keymap);
 
     help.add_line("Key bindings for cgroup memory display:");
     help.add_line("");
@@ -18,33 +21,190 @@ pub fn build_cgroup_tree_help_scene<'a>() -> HelpScene<'a> {
     help.add_key("Home", "Move selection to the top.");
     help.add_key("End", "Move selection to the end.");
     help.add_key(
-        "n",
+        "j / k / g / G",
+        "Vim style aliases for Down, Up, Home and End, unless the key is bound to an action in the configuration file.",
+    );
+    help.add_key("Ctrl-d / Ctrl-u", "Move selection down / up a page.");
+    help.add_key(
+        keymap.key(KeyAction::Search),
+        "Search for a cgroup by name. Enter finishes the query, n / N move to the next / previous match and Esc clears the search.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::SortName),
         "Sort by cgroup name. Pressing again toggles ascending / descending sort order.",
     );
     help.add_key(
-        "s",
+        keymap.key(KeyAction::SortStat),
         "Sort by statistic. Pressing again toggles ascending / descending sort order.",
     );
-    help.add_key("c", "Collapse all expanded nodes.");
-    help.add_key("z", "Select statistic to show.");
-    help.add_key("[", "Move to previous statistic.");
-    help.add_key("]", "Move to next statistic.");
-    help.add_key("p", "Show processes for the selected cgroup.");
     help.add_key(
-        "P",
+        keymap.key(KeyAction::CollapseAll),
+        "Collapse all expanded nodes.",
+    );
+    help.add_key(
+        "1-9",
+        "Expand the tree to show that many levels, collapsing deeper nodes.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::Split),
+        "Open a split pane focused on the selected cgroup.",
+    );
+    help.add_key("Tab", "Switch between the split panes.");
+    help.add_key(
+        "Enter",
+        "Show the main memory statistics and process count of the selected cgroup in a popup. Esc closes it.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleGrowth),
+        "Toggle showing and sorting by the growth since the program started.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleUnescape),
+        "Toggle decoding of systemd \\xNN escapes in cgroup names.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleReclaim),
+        "Toggle marking cgroups throttled by memory.high, showing whether reclaim reduced their memory usage.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleState),
+        "Toggle the glyph showing whether a cgroup is frozen (❄), populated (●) or empty (○).",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleIoRates),
+        "Toggle display of read and write rates from io.stat.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleOomGroup),
+        "Toggle display of the memory.oom.group setting.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleTaskCounts),
+        "Toggle display of thread and process counts.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleProcAverage),
+        "Toggle display of the process count and the average value per process.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleInode),
+        "Toggle display of the cgroup directory inode number.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleSubtreeControl),
+        "Toggle display of the controllers delegated to children from cgroup.subtree_control.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::TogglePopulatedOnly),
+        "Toggle showing only cgroups containing processes (or with descendents that do).",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleHideEmpty),
+        "Toggle hiding cgroups with a zero or unreadable value (unless a descendent has a value).",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleLegend),
+        "Toggle a legend of the value colours by magnitude.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::MarkReference),
+        "Mark the selected cgroup as the reference and show other values relative to it. Pressing again on the reference clears it.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ChooseStat),
+        "Select statistic to show.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::PrevStat),
+        "Move to previous statistic.",
+    );
+    help.add_key(keymap.key(KeyAction::NextStat), "Move to next statistic.");
+    help.add_key(
+        keymap.key(KeyAction::ShowProcs),
+        "Show processes for the selected cgroup.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ShowHierProcs),
         "Show processes for the selected cgroup and all descendents.",
     );
-    help.add_key("t", "Show threads for the selected cgroup.");
     help.add_key(
-        "T",
+        keymap.key(KeyAction::ShowThreads),
+        "Show threads for the selected cgroup.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ShowHierThreads),
         "Show threads for the selected cgroup and all descendents.",
     );
-    help.add_key("r", "Refresh the list.");
-    help.add_key("h", "Shows this help screen.");
-    help.add_key("Esc / q", "Exit the program.");
+    help.add_key(
+        keymap.key(KeyAction::EventsDetail),
+        "Show all memory.events counts for the selected cgroup.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::WatchCGroup),
+        "Pin the selected cgroup and show all of its statistics refreshed every second.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::GraphCGroup),
+        "Graph the memory usage of the selected cgroup over time.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::FlatView),
+        "Show all cgroups in a flat list sorted by value. Enter jumps to the cgroup in the tree.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::Footprint),
+        "Show memory and swap usage as stacked bars for the largest cgroups (see --top).",
+    );
+    help.add_key(
+        keymap.key(KeyAction::Freeze),
+        "Freeze or thaw the selected cgroup and its descendents after confirmation.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::EditNote),
+        "Edit the note attached to the selected cgroup. Notes are saved between sessions. An empty note removes it.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::CopyPath),
+        "Copy the full path of the selected cgroup to the clipboard.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::JumpOwnCGroup),
+        "Select the cgroup containing this process, which is marked in the tree.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::Export),
+        "Export the visible tree nodes to a CSV file in the current directory.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ExportJson),
+        "Export the whole tree with values to a JSON file in the current directory.",
+    );
+    help.add_key(keymap.key(KeyAction::Reload), "Refresh the list.");
+    help.add_key(
+        keymap.key(KeyAction::ToggleUnits),
+        "Toggle memory units between binary (KiB, MiB) and decimal (kB, MB).",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleRawBytes),
+        "Toggle showing memory quantities as exact byte counts.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::TogglePause),
+        "Pause or resume automatic refreshes.",
+    );
+    help.add_key(keymap.key(KeyAction::Step), "Refresh once while paused.");
+    help.add_key(keymap.key(KeyAction::Help), "Shows this help screen.");
+    help.add_key(
+        format!("Esc / {}", keymap.key(KeyAction::Quit)),
+        "Exit the program. Esc closes the split pane first if open.",
+    );
 
     help.add_line("");
-    help.add_line("Press q, h or Esc to exit help");
+    help.add_line(format!(
+        "Press {}, {} or Esc to exit help",
+        keymap.key(KeyAction::Quit),
+        keymap.key(KeyAction::Help)
+    ));
 
     help
 }
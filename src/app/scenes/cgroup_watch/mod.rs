@@ -0,0 +1,262 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use super::{labelled_title, Scene};
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::cgroup::effective_limit;
+use crate::cgroup::stats::{Stat, StatFormat};
+use crate::file_proc::{get_file_processor, FileProcessor};
+use crate::formatters::{format_rate, MemUnits};
+use crate::TermType;
+
+/// Refresh interval for the pinned cgroup
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Limit files shown with their effective values
+const LIMITS: [(&str, &str); 2] = [("memory.max", "Memory"), ("memory.swap.max", "Swap")];
+
+pub struct CGroupWatchScene<'a> {
+    label: Option<&'a str>,
+    cgroup2fs: &'a Path,
+    stats: &'a [Stat<'a>],
+    processors: Vec<Option<Box<dyn FileProcessor>>>,
+    cgroup: PathBuf,
+    values: Vec<Option<usize>>,
+    previous: Vec<Option<usize>>,
+    limits: Vec<(Option<usize>, Option<usize>)>,
+    last_load: Option<Instant>,
+    elapsed: Option<Duration>,
+    per_second: bool,
+    units: MemUnits,
+    next_refresh: Instant,
+}
+
+impl<'a> CGroupWatchScene<'a> {
+    /// Creates a new cgroup watch scene
+    pub fn new(cgroup2fs: &'a Path, stats: &'a [Stat<'a>], options: &'a AppOptions) -> Self {
+        Self {
+            label: options.label.as_deref(),
+            cgroup2fs,
+            stats,
+            processors: stats
+                .iter()
+                .map(|stat| stat.def().and_then(get_file_processor))
+                .collect(),
+            cgroup: PathBuf::new(),
+            values: Vec::new(),
+            previous: Vec::new(),
+            limits: Vec::new(),
+            last_load: None,
+            elapsed: None,
+            per_second: false,
+            units: MemUnits::default(),
+            next_refresh: Instant::now(),
+        }
+    }
+
+    /// Sets the cgroup to watch
+    pub fn set_cgroup(&mut self, cgroup: PathBuf) {
+        if cgroup != self.cgroup {
+            self.values.clear();
+            self.last_load = None;
+        }
+
+        self.cgroup = cgroup;
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+    }
+
+    #[must_use]
+    fn toggle_per_second(&mut self) -> PollResult {
+        self.per_second = !self.per_second;
+
+        Some(vec![])
+    }
+
+    /// Formats a change in value, either as the raw change or normalised per second
+    fn format_delta(&self, stat: &Stat, diff: usize) -> String {
+        match self.elapsed {
+            Some(elapsed) if self.per_second && !elapsed.is_zero() => {
+                let rate = (diff as f64 / elapsed.as_secs_f64()) as usize;
+
+                match stat.format() {
+                    StatFormat::Memory => format_rate(rate, self.units).content.to_string(),
+                    format => format!("{}/s", format.format(rate, self.units).content.trim()),
+                }
+            }
+            _ => stat
+                .format()
+                .format(diff, self.units)
+                .content
+                .trim()
+                .to_string(),
+        }
+    }
+
+    /// Formats a limit, "max" being read as unlimited
+    fn limit_text(&self, limit: Option<usize>) -> String {
+        match limit {
+            Some(usize::MAX) => "max".into(),
+            Some(limit) => StatFormat::Memory
+                .format(limit, self.units)
+                .content
+                .trim()
+                .to_string(),
+            None => "-".into(),
+        }
+    }
+
+    fn value_line(
+        &self,
+        stat: &Stat,
+        value: Option<usize>,
+        previous: Option<usize>,
+    ) -> Line<'static> {
+        let mut spans = vec![Span::from(format!("  {:<16}", stat.short_desc()))];
+
+        match value {
+            Some(value) => {
+                spans.push(stat.format().format(value, self.units));
+
+                // Show the change since the last refresh
+                if let Some(previous) = previous {
+                    let (sign, diff, colour) = if value >= previous {
+                        ("+", value - previous, Color::LightRed)
+                    } else {
+                        ("-", previous - value, Color::LightGreen)
+                    };
+
+                    if diff > 0 {
+                        spans.push(Span::styled(
+                            format!(" {}{}", sign, self.format_delta(stat, diff)),
+                            Style::default().fg(colour),
+                        ));
+                    }
+                }
+            }
+            None => spans.push(Span::styled(
+                "<Not available>",
+                Style::default().add_modifier(Modifier::DIM),
+            )),
+        }
+
+        Line::from(spans)
+    }
+}
+
+impl<'a> Scene for CGroupWatchScene<'a> {
+    /// Reloads the statistics for the pinned cgroup
+    fn reload(&mut self) {
+        let mut path = self.cgroup2fs.to_path_buf();
+        path.extend(&self.cgroup);
+
+        // Record the time since the last load for per second changes
+        let now = Instant::now();
+        self.elapsed = self.last_load.map(|last| now.duration_since(last));
+        self.last_load = Some(now);
+
+        self.previous = std::mem::take(&mut self.values);
+        self.values = self
+            .processors
+            .iter()
+            .map(|processor| processor.as_ref()?.get_stat(&path).ok())
+            .collect();
+
+        // Read the configured and effective limits
+        self.limits = LIMITS
+            .iter()
+            .map(|(file, _)| effective_limit(self.cgroup2fs, &self.cgroup, file))
+            .collect();
+
+        // Calculate next refresh time
+        self.next_refresh = Instant::now().checked_add(WATCH_INTERVAL).unwrap();
+    }
+
+    /// Draws the cgroup watch scene
+    fn draw(&mut self, terminal: &mut TermType) -> Result<(), io::Error> {
+        terminal.draw(|f| {
+            // Get the size of the frame
+            let size = f.size();
+
+            // Create the block
+            let block = Block::default()
+                .title(labelled_title(
+                    self.label,
+                    &format!(
+                        "Watching /{} every {:?}, showing change {} (press d to change, q to return)",
+                        self.cgroup.to_string_lossy(),
+                        WATCH_INTERVAL,
+                        if self.per_second {
+                            "per second"
+                        } else {
+                            "since last refresh"
+                        }
+                    ),
+                ))
+                .borders(Borders::ALL);
+
+            // Build the text
+            let mut text: Vec<Line> = self
+                .stats
+                .iter()
+                .enumerate()
+                .filter(|(_, stat)| !stat.proc_only())
+                .map(|(i, stat)| {
+                    self.value_line(
+                        stat,
+                        self.values.get(i).copied().flatten(),
+                        self.previous.get(i).copied().flatten(),
+                    )
+                })
+                .collect();
+
+            // Add the configured and effective limits
+            text.push(Line::from(""));
+            text.push(Line::from(Span::styled(
+                format!("  {:<16}{:>14}{:>14}", "Limit", "Configured", "Effective"),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+
+            for ((_, desc), (configured, effective)) in LIMITS.iter().zip(&self.limits) {
+                text.push(Line::from(format!(
+                    "  {:<16}{:>14}{:>14}",
+                    desc,
+                    self.limit_text(*configured),
+                    self.limit_text(*effective)
+                )));
+            }
+
+            // Create the paragraph
+            let para = Paragraph::new(text).block(block);
+
+            // Draw the paragraph
+            f.render_widget(para, size);
+        })?;
+
+        Ok(())
+    }
+
+    /// Calculates the time left before the statistics should be reloaded, None returned if overdue
+    fn time_to_refresh(&self) -> Option<Duration> {
+        self.next_refresh.checked_duration_since(Instant::now())
+    }
+
+    /// Key event
+    fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(vec![Action::Scene(AppScene::CGroupTree)]),
+            KeyCode::Char('r') => Some(vec![Action::Reload]),
+            KeyCode::Char('d') => self.toggle_per_second(),
+            _ => None,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::io;
 
 use crossterm::event::{KeyCode, KeyEvent};
@@ -5,17 +6,20 @@ use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 
-use super::Scene;
+use super::{labelled_title, vim_nav_code, Scene};
+use crate::app::keymap::{KeyAction, KeyMap};
 use crate::app::{Action, AppScene, PollResult};
 use crate::TermType;
 
 enum HelpLine<'a> {
-    Line(&'a str),
-    Key(&'a str, &'a str),
+    Line(Cow<'a, str>),
+    Key(Cow<'a, str>, &'a str),
 }
 
 #[derive(Default)]
 pub struct HelpScene<'a> {
+    label: Option<&'a str>,
+    keymap: Option<&'a KeyMap>,
     lines: Vec<HelpLine<'a>>,
     max_key: usize,
     changed: bool,
@@ -23,21 +27,27 @@ pub struct HelpScene<'a> {
     max_scroll_x: u16,
     cur_scroll_y: u16,
     max_scroll_y: u16,
+    page_size: u16,
 }
 
 impl<'a> HelpScene<'a> {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(label: Option<&'a str>, keymap: &'a KeyMap) -> Self {
+        Self {
+            label,
+            keymap: Some(keymap),
+            ..Default::default()
+        }
     }
 
-    pub fn add_line(&mut self, line: &'a str) {
-        self.lines.push(HelpLine::Line(line));
+    pub fn add_line(&mut self, line: impl Into<Cow<'a, str>>) {
+        self.lines.push(HelpLine::Line(line.into()));
         self.changed = true;
     }
 
-    pub fn add_key(&mut self, key: &'a str, desc: &'a str) {
-        self.lines.push(HelpLine::Key(key, desc));
+    pub fn add_key(&mut self, key: impl Into<Cow<'a, str>>, desc: &'a str) {
+        let key = key.into();
         self.max_key = std::cmp::max(self.max_key, key.len());
+        self.lines.push(HelpLine::Key(key, desc));
         self.changed = true;
     }
 
@@ -61,6 +71,18 @@ impl<'a> HelpScene<'a> {
         }
     }
 
+    #[must_use]
+    fn scroll_help_to(&mut self, y: u16) -> PollResult {
+        let y = std::cmp::min(y, self.max_scroll_y);
+
+        if y != self.cur_scroll_y {
+            self.cur_scroll_y = y;
+            Some(vec![])
+        } else {
+            None
+        }
+    }
+
     #[must_use]
     fn scroll_help_left(&mut self) -> PollResult {
         if self.cur_scroll_x > 0 {
@@ -93,14 +115,16 @@ impl<'a> Scene for HelpScene<'a> {
             let size = f.size();
 
             // Create block
-            let block = Block::default().title("Help").borders(Borders::ALL);
+            let block = Block::default()
+                .title(labelled_title(self.label, "Help"))
+                .borders(Borders::ALL);
 
             // Create text
             let text: Vec<Line<'a>> = self
                 .lines
                 .iter()
                 .map(|line| match &line {
-                    HelpLine::Line(line) => Line::from(Span::<'a>::raw(*line)),
+                    HelpLine::Line(line) => Line::from(Span::<'a>::raw(line.clone())),
                     HelpLine::Key(key, desc) => Line::from(vec![
                         Span::styled(
                             format!("  {:<width$}  ", key, width = self.max_key),
@@ -117,6 +141,8 @@ impl<'a> Scene for HelpScene<'a> {
             let lines = text.len() as u16;
             let height = inner_rect.height;
 
+            self.page_size = height;
+
             self.max_scroll_y = lines.saturating_sub(height);
 
             if self.cur_scroll_y > self.max_scroll_y {
@@ -145,14 +171,29 @@ impl<'a> Scene for HelpScene<'a> {
     }
 
     /// Key event
-    #[must_use]
     fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
-        match key_event.code {
-            KeyCode::Char('q') | KeyCode::Char('h') | KeyCode::Esc => {
-                Some(vec![Action::Scene(AppScene::CGroupTree)])
-            }
+        let exit = self.keymap.is_some_and(|keymap| {
+            keymap
+                .action(key_event.code, &[KeyAction::Quit, KeyAction::Help])
+                .is_some()
+        });
+
+        if exit {
+            return Some(vec![Action::Scene(AppScene::CGroupTree)]);
+        }
+
+        match vim_nav_code(key_event, |_| false) {
+            KeyCode::Esc => Some(vec![Action::Scene(AppScene::CGroupTree)]),
             KeyCode::Down => self.scroll_help_down(),
             KeyCode::Up => self.scroll_help_up(),
+            KeyCode::PageDown => {
+                self.scroll_help_to(self.cur_scroll_y.saturating_add(self.page_size))
+            }
+            KeyCode::PageUp => {
+                self.scroll_help_to(self.cur_scroll_y.saturating_sub(self.page_size))
+            }
+            KeyCode::Home => self.scroll_help_to(0),
+            KeyCode::End => self.scroll_help_to(self.max_scroll_y),
             KeyCode::Left => self.scroll_help_left(),
             KeyCode::Right => self.scroll_help_right(),
             _ => None,
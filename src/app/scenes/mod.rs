@@ -1,18 +1,70 @@
 use std::io;
 use std::time::Duration;
 
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Margin, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{
+    Block,
+    Borders,
+    Clear,
+    Paragraph,
+    Scrollbar,
+    ScrollbarOrientation,
+    ScrollbarState,
+};
+use ratatui::Frame;
 
 use super::PollResult;
 use crate::TermType;
 
+pub mod cgroup_events;
+pub mod cgroup_flat;
+pub mod cgroup_footprint;
+pub mod cgroup_graph;
 pub mod cgroup_tree;
 pub mod cgroup_tree_help;
+pub mod cgroup_watch;
 pub mod help;
+pub mod proc_detail;
 pub mod procs;
 pub mod procs_help;
 pub mod stat_choose;
 
+/// Title warning shown when a reload takes longer than the refresh interval
+pub fn slow_reload_warning(load_time: Duration, interval: Duration) -> Option<&'static str> {
+    if load_time > interval {
+        Some(" (refresh falling behind, consider a longer --interval)")
+    } else {
+        None
+    }
+}
+
+/// Prepends the session label to a title
+pub fn labelled_title(label: Option<&str>, title: &str) -> String {
+    match label {
+        Some(label) => format!("[{}] {}", label, title),
+        None => title.to_string(),
+    }
+}
+
+/// Translates vim style navigation keys to the equivalent cursor keys. j, k, g and G are left
+/// alone if the configuration binds them to another action
+pub fn vim_nav_code(key_event: KeyEvent, bound: impl Fn(KeyCode) -> bool) -> KeyCode {
+    let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key_event.code {
+        KeyCode::Char('d') if ctrl => KeyCode::PageDown,
+        KeyCode::Char('u') if ctrl => KeyCode::PageUp,
+        code if bound(code) => code,
+        KeyCode::Char('j') => KeyCode::Down,
+        KeyCode::Char('k') => KeyCode::Up,
+        KeyCode::Char('g') => KeyCode::Home,
+        KeyCode::Char('G') => KeyCode::End,
+        code => code,
+    }
+}
+
 pub trait Scene {
     fn reload(&mut self);
     fn draw(&mut self, terminal: &mut TermType) -> Result<(), io::Error>;
@@ -21,3 +73,61 @@ pub trait Scene {
         Some(Duration::MAX)
     }
 }
+
+/// Draws a vertical scrollbar on the right hand border of a block if the content doesn't fit
+pub fn render_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    content_len: usize,
+    viewport_len: usize,
+    position: usize,
+) {
+    if content_len <= viewport_len {
+        return;
+    }
+
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None);
+
+    let mut state = ScrollbarState::new(content_len)
+        .viewport_content_length(viewport_len)
+        .position(position);
+
+    frame.render_stateful_widget(
+        scrollbar,
+        area.inner(&Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut state,
+    );
+}
+
+/// Draws lines in a bordered box centred over the frame, sized to fit the text where possible
+pub fn render_popup(frame: &mut Frame, title: &str, text: Vec<Line>) {
+    let size = frame.size();
+
+    let width = text
+        .iter()
+        .map(Line::width)
+        .chain([title.chars().count()])
+        .max()
+        .unwrap_or(0)
+        .saturating_add(4)
+        .min(size.width as usize) as u16;
+
+    let height = text.len().saturating_add(2).min(size.height as usize) as u16;
+
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default().title(title).borders(Borders::ALL);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
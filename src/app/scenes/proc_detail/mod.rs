@@ -0,0 +1,170 @@
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use super::{labelled_title, Scene};
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::formatters::{format_mem_qty, MemUnits};
+use crate::proc::load_smaps_rollup;
+use crate::TermType;
+
+pub struct ProcDetailScene<'a> {
+    label: Option<&'a str>,
+    interval: Duration,
+    proc_root: &'a Path,
+    pid: usize,
+    cmd: String,
+    values: Result<Vec<(String, usize)>, String>,
+    units: MemUnits,
+    next_refresh: Instant,
+}
+
+impl<'a> ProcDetailScene<'a> {
+    /// Creates a new process detail scene
+    pub fn new(options: &'a AppOptions) -> Self {
+        Self {
+            label: options.label.as_deref(),
+            interval: options.interval,
+            proc_root: options.proc_root.as_deref().unwrap_or(Path::new("/proc")),
+            pid: 0,
+            cmd: String::new(),
+            values: Ok(Vec::new()),
+            units: MemUnits::default(),
+            next_refresh: Instant::now(),
+        }
+    }
+
+    /// Sets the process to show
+    pub fn set_proc(&mut self, pid: usize, cmd: String) {
+        self.pid = pid;
+        self.cmd = cmd;
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+    }
+
+    /// Returns the path of the smaps_rollup file for the process
+    fn smaps_rollup_path(&self) -> PathBuf {
+        self.proc_root
+            .join(self.pid.to_string())
+            .join("smaps_rollup")
+    }
+
+    /// Sums the named values
+    fn sum(values: &[(String, usize)], keys: &[&str]) -> usize {
+        values
+            .iter()
+            .filter(|(key, _)| keys.contains(&key.as_str()))
+            .fold(0, |sum, (_, value)| sum.saturating_add(*value))
+    }
+
+    fn value_line(&self, desc: &str, value: usize, style: Style) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(format!("  {:<16}", desc), style),
+            format_mem_qty(value, self.units),
+        ])
+    }
+}
+
+impl<'a> Scene for ProcDetailScene<'a> {
+    /// Reloads the process details
+    fn reload(&mut self) {
+        let path = self.smaps_rollup_path();
+
+        self.values = load_smaps_rollup(self.proc_root, self.pid).map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => format!(
+                "Permission denied reading {}. Run as root or as the process owner.",
+                path.display()
+            ),
+            ErrorKind::NotFound => "The process no longer exists".into(),
+            _ => format!("Unable to read {}: {}", path.display(), e),
+        });
+
+        // Calculate next refresh time
+        self.next_refresh = Instant::now().checked_add(self.interval).unwrap();
+    }
+
+    /// Draws the process detail scene
+    fn draw(&mut self, terminal: &mut TermType) -> Result<(), io::Error> {
+        terminal.draw(|f| {
+            // Get the size of the frame
+            let size = f.size();
+
+            // Create the block
+            let block = Block::default()
+                .title(labelled_title(
+                    self.label,
+                    &format!(
+                        "Memory Detail for PID {} {} (press q to return)",
+                        self.pid, self.cmd
+                    ),
+                ))
+                .borders(Borders::ALL);
+
+            // Build the text
+            let text = match &self.values {
+                Err(msg) => vec![Line::from(Span::styled(
+                    msg.clone(),
+                    Style::default().fg(Color::Red),
+                ))],
+                Ok(values) => {
+                    let bold = Style::default().add_modifier(Modifier::BOLD);
+                    let dim = Style::default().add_modifier(Modifier::DIM);
+
+                    let mut text = vec![
+                        self.value_line("PSS", Self::sum(values, &["Pss"]), bold),
+                        self.value_line(
+                            "USS",
+                            Self::sum(values, &["Private_Clean", "Private_Dirty"]),
+                            bold,
+                        ),
+                        self.value_line(
+                            "Shared",
+                            Self::sum(values, &["Shared_Clean", "Shared_Dirty"]),
+                            bold,
+                        ),
+                        self.value_line("RSS", Self::sum(values, &["Rss"]), bold),
+                        Line::from(""),
+                    ];
+
+                    text.extend(
+                        values
+                            .iter()
+                            .map(|(key, value)| self.value_line(key, *value, dim)),
+                    );
+
+                    text
+                }
+            };
+
+            // Create the paragraph
+            let para = Paragraph::new(text).block(block);
+
+            // Draw the paragraph
+            f.render_widget(para, size);
+        })?;
+
+        Ok(())
+    }
+
+    /// Calculates the time left before the details should be reloaded, None returned if overdue
+    fn time_to_refresh(&self) -> Option<Duration> {
+        self.next_refresh.checked_duration_since(Instant::now())
+    }
+
+    /// Key event
+    fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(vec![Action::Scene(AppScene::Procs)]),
+            KeyCode::Char('r') => Some(vec![Action::Reload]),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Paragraph};
+use ratatui::Frame;
+
+const SUFFIXES: [&str; 7] = ["", "K", "M", "G", "T", "P", "E"];
+
+/// Counts values in power of 2 buckets. Returns the lower bound and count of each bucket
+/// from the lowest to the highest bucket used
+fn build_buckets(values: impl Iterator<Item = usize>) -> Vec<(usize, u64)> {
+    // Bucket 0 holds zero values, bucket n holds values from 2^(n-1) to 2^n - 1
+    let mut counts = [0_u64; usize::BITS as usize + 1];
+
+    for value in values {
+        let bucket = match value {
+            0 => 0,
+            v => v.ilog2() as usize + 1,
+        };
+
+        counts[bucket] += 1;
+    }
+
+    let Some(first) = counts.iter().position(|&c| c > 0) else {
+        return Vec::new();
+    };
+
+    let last = counts.iter().rposition(|&c| c > 0).unwrap_or(first);
+
+    (first..=last)
+        .map(|bucket| {
+            let lower = match bucket {
+                0 => 0,
+                b => 1 << (b - 1),
+            };
+
+            (lower, counts[bucket])
+        })
+        .collect()
+}
+
+/// Formats a power of 2 byte count compactly, e.g. 512K or 4M
+fn bucket_label(bytes: usize) -> String {
+    if bytes == 0 {
+        return "0".into();
+    }
+
+    let power = std::cmp::min(bytes.ilog2() as usize / 10, SUFFIXES.len() - 1);
+
+    format!("{}{}", bytes >> (power * 10), SUFFIXES[power])
+}
+
+/// Draws a histogram of the process statistic values
+pub fn render_histogram(frame: &mut Frame, block: Block, values: impl Iterator<Item = usize>) {
+    let size = frame.size();
+    let buckets = build_buckets(values);
+
+    if buckets.is_empty() {
+        frame.render_widget(Paragraph::new("No values to show").block(block), size);
+        return;
+    }
+
+    let labels: Vec<String> = buckets
+        .iter()
+        .map(|(lower, _)| bucket_label(*lower))
+        .collect();
+
+    let bar_width = labels.iter().map(|l| l.len()).max().unwrap_or(1).max(3);
+
+    let bars: Vec<Bar> = buckets
+        .iter()
+        .zip(labels)
+        .map(|((_, count), label)| Bar::default().value(*count).label(Line::from(label)))
+        .collect();
+
+    let chart = BarChart::default()
+        .block(block)
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width as u16)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::LightBlue))
+        .value_style(Style::default().fg(Color::Black).bg(Color::LightBlue));
+
+    frame.render_widget(chart, size);
+}
@@ -1,50 +1,154 @@
+mod histogram;
+mod status;
 mod table;
+mod tree;
 
 use std::ffi::OsStr;
-use std::io;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
+use std::{cmp, io};
 
 use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::block::{Position, Title};
 use ratatui::widgets::{Block, Borders};
 
+use self::histogram::render_histogram;
+use self::status::ProcStatusPopup;
 use self::table::ProcsTable;
-use super::Scene;
-use crate::app::{Action, AppScene, PollResult};
-use crate::cgroup::stats::{ProcStatType, STATS};
+use self::tree::ProcsTree;
+use super::{labelled_title, slow_reload_warning, vim_nav_code, Scene};
+use crate::app::keymap::KeyAction;
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::cgroup::stats::{ProcStatType, Stat};
 use crate::cgroup::CGroupSortOrder;
-use crate::proc::ProcSortOrder;
+use crate::formatters::{export_file_name, MemUnits};
+use crate::proc::{
+    own_proc_root,
+    send_signal,
+    Proc,
+    ProcLoadOptions,
+    ProcSortOrder,
+    ProcStateFilter,
+};
 use crate::TermType;
 
+/// Amount to adjust the process row limit by
+const LIMIT_STEP: usize = 10;
+
+/// A signal waiting for confirmation before being sent
+struct PendingSignal {
+    pid: usize,
+    cmd: String,
+    signal: libc::c_int,
+    name: &'static str,
+}
+
+/// Key actions available in the process scene
+const KEY_ACTIONS: [KeyAction; 32] = [
+    KeyAction::Quit,
+    KeyAction::ShowProcs,
+    KeyAction::ShowThreads,
+    KeyAction::ShowHierProcs,
+    KeyAction::ShowHierThreads,
+    KeyAction::SortPid,
+    KeyAction::SortName,
+    KeyAction::SortStat,
+    KeyAction::PrevStat,
+    KeyAction::NextStat,
+    KeyAction::ToggleThreads,
+    KeyAction::ToggleChildren,
+    KeyAction::IncreaseLimit,
+    KeyAction::DecreaseLimit,
+    KeyAction::Help,
+    KeyAction::ToggleWatch,
+    KeyAction::ToggleComm,
+    KeyAction::ToggleContainer,
+    KeyAction::ToggleHistogram,
+    KeyAction::ToggleUserColours,
+    KeyAction::ToggleProcTree,
+    KeyAction::ProcDetail,
+    KeyAction::Terminate,
+    KeyAction::Kill,
+    KeyAction::Export,
+    KeyAction::Filter,
+    KeyAction::StateFilter,
+    KeyAction::Reload,
+    KeyAction::TogglePause,
+    KeyAction::ToggleUnits,
+    KeyAction::ToggleRawBytes,
+    KeyAction::Step,
+];
+
 pub struct ProcsScene<'a> {
-    debug: bool,
+    options: &'a AppOptions,
     cgroup2fs: &'a Path,
+    stats: &'a [Stat<'a>],
     cgroup: PathBuf,
     sort: ProcSortOrder,
     proc_sort: ProcSortOrder,
     stat: usize,
     threads: bool,
     include_children: bool,
+    limit: Option<usize>,
+    watch: bool,
+    histogram: bool,
+    user_colours: bool,
+    proc_tree: bool,
+    load_options: ProcLoadOptions<'a>,
+    full_reload: bool,
+    status: Option<String>,
+    paused: bool,
+    units: MemUnits,
+    filter: Option<String>,
+    filter_input: bool,
+    state_filter: ProcStateFilter,
+    signals: bool,
+    pending_signal: Option<PendingSignal>,
+    status_popup: Option<ProcStatusPopup>,
     table: ProcsTable<'a>,
+    tree: ProcsTree<'a>,
     next_refresh: Instant,
+    load_time: Duration,
     draws: usize,
     loads: usize,
 }
 
 impl<'a> ProcsScene<'a> {
     /// Creates a new process scene
-    pub fn new(cgroup2fs: &'a Path, debug: bool) -> Self {
+    pub fn new(cgroup2fs: &'a Path, stats: &'a [Stat<'a>], options: &'a AppOptions) -> Self {
         Self {
-            debug,
+            options,
             cgroup2fs,
+            stats,
             cgroup: PathBuf::new(),
             sort: ProcSortOrder::CmdAsc,
             proc_sort: ProcSortOrder::CmdAsc,
             stat: 0,
             threads: false,
             include_children: false,
+            limit: options.proc_limit,
+            watch: false,
+            histogram: false,
+            user_colours: false,
+            proc_tree: false,
+            load_options: ProcLoadOptions {
+                proc_root: options.proc_root.as_deref(),
+                ..Default::default()
+            },
+            full_reload: true,
+            status: None,
+            paused: false,
+            units: MemUnits::default(),
+            filter: None,
+            filter_input: false,
+            state_filter: ProcStateFilter::default(),
+            signals: options.proc_root.as_deref().is_some_and(own_proc_root),
+            pending_signal: None,
+            status_popup: None,
             table: Default::default(),
+            tree: Default::default(),
             next_refresh: Instant::now(),
+            load_time: Duration::ZERO,
             draws: 0,
             loads: 0,
         }
@@ -57,19 +161,40 @@ impl<'a> ProcsScene<'a> {
         }
 
         self.cgroup = path;
+        self.full_reload = true;
+        self.status_popup = None;
 
         self.table.reset();
+        self.tree.reset();
+    }
+
+    /// Sets whether automatic refreshes are paused
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+        self.full_reload = true;
     }
 
     /// Sets the statistic to display
     pub fn set_stat(&mut self, stat: usize) {
         self.stat = stat;
+        self.full_reload = true;
         self.resolve_sort();
     }
 
+    /// Returns the sort order in use
+    pub fn proc_sort(&self) -> ProcSortOrder {
+        self.proc_sort
+    }
+
     /// Sets the sort order to use
     pub fn set_sort(&mut self, sort: ProcSortOrder) {
         self.proc_sort = sort;
+        self.full_reload = true;
         self.resolve_sort();
     }
 
@@ -81,6 +206,7 @@ impl<'a> ProcsScene<'a> {
             CGroupSortOrder::StatAsc => self.proc_sort = ProcSortOrder::StatAsc,
             CGroupSortOrder::StatDsc => self.proc_sort = ProcSortOrder::StatDsc,
         }
+        self.full_reload = true;
         self.resolve_sort();
     }
 
@@ -88,6 +214,7 @@ impl<'a> ProcsScene<'a> {
     pub fn set_mode(&mut self, threads: bool, include_children: bool) {
         self.threads = threads;
         self.include_children = include_children;
+        self.full_reload = true;
     }
 
     #[must_use]
@@ -120,8 +247,301 @@ impl<'a> ProcsScene<'a> {
         Some(vec![Action::ProcSort(new_sort), Action::Reload])
     }
 
+    #[must_use]
+    fn toggle_watch(&mut self) -> PollResult {
+        self.watch = !self.watch;
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn toggle_histogram(&mut self) -> PollResult {
+        if self.stats[self.stat].proc_stat_type() == ProcStatType::None {
+            return None;
+        }
+
+        self.histogram = !self.histogram;
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn toggle_user_colours(&mut self) -> PollResult {
+        self.user_colours = !self.user_colours;
+
+        self.full_reload()
+    }
+
+    #[must_use]
+    fn toggle_proc_tree(&mut self) -> PollResult {
+        self.proc_tree = !self.proc_tree;
+
+        if self.proc_tree {
+            self.build_proc_tree();
+        }
+
+        Some(vec![])
+    }
+
+    /// Builds the process tree from the processes shown in the table
+    fn build_proc_tree(&mut self) {
+        let procs: Vec<&Proc> = self.table.shown_procs().collect();
+
+        self.tree
+            .build_tree(&procs, &self.stats[self.stat], self.units);
+    }
+
+    /// Returns the selected process in the table or tree
+    fn selected_proc(&self) -> Option<&Proc> {
+        if self.proc_tree {
+            self.tree
+                .selected_pid()
+                .and_then(|pid| self.table.proc_by_pid(pid))
+        } else {
+            self.table.selected_proc()
+        }
+    }
+
+    /// Returns false with a status message if the PIDs can't be looked up in /proc
+    fn check_proc_lookups(&mut self) -> bool {
+        if self.options.proc_root.is_none() {
+            self.status = Some("Process lookups are disabled with --root".into());
+            return false;
+        }
+
+        true
+    }
+
+    #[must_use]
+    fn proc_detail(&mut self) -> PollResult {
+        if !self.check_proc_lookups() {
+            return Some(vec![]);
+        }
+
+        self.selected_proc().map(|proc| {
+            vec![
+                Action::ProcDetail(proc.pid, proc.cmd.clone()),
+                Action::Scene(AppScene::ProcDetail),
+            ]
+        })
+    }
+
+    #[must_use]
+    fn open_status_popup(&mut self) -> PollResult {
+        if !self.check_proc_lookups() {
+            return Some(vec![]);
+        }
+
+        let proc = self.selected_proc()?;
+        let mut popup = ProcStatusPopup::new(proc.pid, &proc.cmd);
+
+        if let Some(proc_root) = self.options.proc_root.as_deref() {
+            popup.reload(proc_root);
+        }
+
+        self.status_popup = Some(popup);
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn status_popup_key(&mut self, code: KeyCode) -> PollResult {
+        match code {
+            KeyCode::Esc | KeyCode::Enter => {
+                self.status_popup = None;
+                Some(vec![])
+            }
+            code => match self.options.keymap.action(code, &KEY_ACTIONS) {
+                Some(KeyAction::Quit) => {
+                    self.status_popup = None;
+                    Some(vec![])
+                }
+                Some(KeyAction::Reload) => Some(vec![Action::Reload]),
+                _ => None,
+            },
+        }
+    }
+
+    #[must_use]
+    fn request_signal(&mut self, signal: libc::c_int, name: &'static str) -> PollResult {
+        if !self.check_proc_lookups() {
+            return Some(vec![]);
+        }
+
+        // PIDs from another PID namespace would signal the wrong process
+        if !self.signals {
+            self.status =
+                Some("Signals are disabled as --proc-root isn't this system's /proc".into());
+            return Some(vec![]);
+        }
+
+        let proc = self.selected_proc()?;
+
+        let pending = PendingSignal {
+            pid: proc.pid,
+            cmd: proc.cmd.clone(),
+            signal,
+            name,
+        };
+
+        self.pending_signal = Some(pending);
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn confirm_signal_key(&mut self, code: KeyCode) -> PollResult {
+        let pending = self.pending_signal.take()?;
+
+        if code != KeyCode::Char('y') {
+            return Some(vec![]);
+        }
+
+        match send_signal(pending.pid, pending.signal) {
+            Ok(()) => {
+                self.status = Some(format!("Sent {} to {}", pending.name, pending.pid));
+                self.full_reload()
+            }
+            Err(e) => {
+                self.status = Some(format!(
+                    "Unable to send {} to {}: {}",
+                    pending.name, pending.pid, e
+                ));
+                Some(vec![])
+            }
+        }
+    }
+
+    #[must_use]
+    fn toggle_comm(&mut self) -> PollResult {
+        self.load_options.prefer_comm = !self.load_options.prefer_comm;
+
+        self.full_reload()
+    }
+
+    #[must_use]
+    fn toggle_container(&mut self) -> PollResult {
+        self.load_options.container = !self.load_options.container;
+
+        self.full_reload()
+    }
+
+    #[must_use]
+    fn start_filter(&mut self) -> PollResult {
+        self.filter = Some(self.filter.take().unwrap_or_default());
+        self.filter_input = true;
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn clear_filter(&mut self) -> PollResult {
+        self.filter = None;
+        self.filter_input = false;
+
+        self.apply_filter()
+    }
+
+    #[must_use]
+    fn filter_key(&mut self, code: KeyCode) -> PollResult {
+        let filter = self.filter.as_mut()?;
+
+        match code {
+            KeyCode::Esc => return self.clear_filter(),
+            KeyCode::Enter => {
+                self.filter_input = false;
+
+                if filter.is_empty() {
+                    self.filter = None;
+                }
+
+                return Some(vec![]);
+            }
+            KeyCode::Backspace => {
+                filter.pop();
+            }
+            KeyCode::Char(c) => filter.push(c),
+            _ => return None,
+        }
+
+        self.apply_filter()
+    }
+
+    #[must_use]
+    fn cycle_state_filter(&mut self) -> PollResult {
+        self.state_filter = self.state_filter.next();
+        self.table.set_state_filter(self.state_filter);
+
+        self.apply_filter()
+    }
+
+    /// Applies the filter to the loaded processes without reloading them
+    #[must_use]
+    fn apply_filter(&mut self) -> PollResult {
+        self.table
+            .set_filter(self.filter.clone().filter(|f| !f.is_empty()));
+        self.table
+            .rebuild(self.threads, &self.stats[self.stat], self.sort);
+
+        if self.proc_tree {
+            self.build_proc_tree();
+        }
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn full_reload(&mut self) -> PollResult {
+        self.full_reload = true;
+
+        Some(vec![Action::Reload])
+    }
+
+    #[must_use]
+    fn export_visible(&mut self) -> PollResult {
+        let path = export_file_name("procs", "csv");
+
+        self.status = Some(
+            match self
+                .table
+                .export_visible_csv(&path, self.threads, &self.stats[self.stat])
+            {
+                Ok(()) => format!("Exported to {}", path.display()),
+                Err(e) => format!("Export failed: {}", e),
+            },
+        );
+
+        Some(vec![])
+    }
+
+    #[must_use]
+    fn increase_limit(&mut self) -> PollResult {
+        self.limit.map(|limit| {
+            self.limit = Some(limit + LIMIT_STEP);
+            vec![Action::Reload]
+        })
+    }
+
+    #[must_use]
+    fn decrease_limit(&mut self) -> PollResult {
+        let limit = self.limit.unwrap_or_else(|| self.table.proc_count());
+
+        // Round down to the next lowest multiple of the step size
+        let new_limit = cmp::max(
+            LIMIT_STEP,
+            limit.saturating_sub(1) / LIMIT_STEP * LIMIT_STEP,
+        );
+
+        if self.limit != Some(new_limit) {
+            self.limit = Some(new_limit);
+            Some(vec![Action::Reload])
+        } else {
+            None
+        }
+    }
+
     fn resolve_sort(&mut self) {
-        self.sort = if STATS[self.stat].proc_stat_type() == ProcStatType::None {
+        self.sort = if self.stats[self.stat].proc_stat_type() == ProcStatType::None {
             match self.proc_sort {
                 ProcSortOrder::StatAsc => ProcSortOrder::PidAsc,
                 ProcSortOrder::StatDsc => ProcSortOrder::PidDsc,
@@ -138,14 +558,14 @@ impl<'a> ProcsScene<'a> {
 
         loop {
             new_stat = if up {
-                (new_stat + 1) % STATS.len()
+                (new_stat + 1) % self.stats.len()
             } else if new_stat == 0 {
-                STATS.len() - 1
+                self.stats.len() - 1
             } else {
                 new_stat - 1
             };
 
-            if STATS[new_stat].proc_stat_type() != ProcStatType::None {
+            if self.stats[new_stat].proc_stat_type() != ProcStatType::None {
                 break;
             }
         }
@@ -157,19 +577,48 @@ impl<'a> ProcsScene<'a> {
 impl<'a> Scene for ProcsScene<'a> {
     /// Reloads the process scene
     fn reload(&mut self) {
-        // Build the tree
-        self.table.build_table(
-            self.cgroup2fs,
-            &self.cgroup,
-            self.threads,
-            self.include_children,
-            self.stat,
-            self.sort,
-        );
+        let start = Instant::now();
+        let stat = &self.stats[self.stat];
+
+        // Reload just the selected process if watching it, falling back to a full reload
+        let reloaded = !self.full_reload
+            && self.watch
+            && !self.proc_tree
+            && self.table.reload_selected(self.threads, stat, self.sort);
+
+        if !reloaded {
+            // Build the table
+            self.table.set_limit(self.limit);
+            self.table.set_load_options(self.load_options);
+            self.table.set_units(self.units);
+            self.table.set_user_colours(self.user_colours);
+            self.table.build_table(
+                self.cgroup2fs,
+                &self.cgroup,
+                self.threads,
+                self.include_children,
+                stat,
+                self.sort,
+            );
+            self.full_reload = false;
+
+            if self.proc_tree {
+                self.build_proc_tree();
+            }
+        }
+
+        // Refresh the status popup
+        if let (Some(popup), Some(proc_root)) =
+            (&mut self.status_popup, self.options.proc_root.as_deref())
+        {
+            popup.reload(proc_root);
+        }
+
         self.loads += 1;
+        self.load_time = start.elapsed();
 
         // Calculate next refresh time
-        self.next_refresh = Instant::now().checked_add(Duration::from_secs(5)).unwrap();
+        self.next_refresh = Instant::now().checked_add(self.options.interval).unwrap();
     }
 
     /// Draws the process scene
@@ -180,7 +629,7 @@ impl<'a> Scene for ProcsScene<'a> {
             // Create the title
             let mut cgroup_str = self.cgroup.to_string_lossy();
 
-            if cgroup_str == "" {
+            if cgroup_str.is_empty() {
                 cgroup_str = "/".into();
             }
 
@@ -193,57 +642,221 @@ impl<'a> Scene for ProcsScene<'a> {
 
             let mut title = format!("{} for {}", ptype, cgroup_str);
 
-            if self.debug {
+            let stat = &self.stats[self.stat];
+
+            if stat.proc_stat_type() != ProcStatType::None {
+                let (total, count) = self.table.stat_total();
+
                 title += &format!(
-                    " ({} loads, {} draws, {:?})",
+                    " - {} total {}",
+                    stat.proc_short_desc(),
+                    stat.proc_format().format(total, self.units).content.trim()
+                );
+
+                if let Some(average) = total.checked_div(count) {
+                    title += &format!(
+                        ", average {}",
+                        stat.proc_format()
+                            .format(average, self.units)
+                            .content
+                            .trim()
+                    );
+                }
+            }
+
+            if let Some(limit) = self.limit {
+                title += &format!(" (top {})", limit);
+            }
+
+            if self.load_options.prefer_comm {
+                title += " (showing comm)";
+            }
+
+            if self.watch {
+                title += " (watching selected)";
+            }
+
+            if self.proc_tree {
+                title += " (tree)";
+            }
+
+            if self.histogram {
+                title += " (histogram)";
+            }
+
+            if self.paused {
+                title += " (paused)";
+            }
+
+            if let Some(filter) = &self.filter {
+                if self.filter_input {
+                    title += &format!(" (filter: {}_)", filter);
+                } else {
+                    title += &format!(" (filter: {})", filter);
+                }
+            }
+
+            if self.state_filter != ProcStateFilter::All {
+                title += &format!(" (state: {})", self.state_filter.desc());
+            }
+
+            if self.options.debug {
+                title += &format!(
+                    " ({} loads, {} draws, {:?} load, {:?})",
                     self.loads,
                     self.draws,
+                    self.load_time,
                     self.table.selected()
                 );
             }
 
+            if let Some(warning) = slow_reload_warning(self.load_time, self.options.interval) {
+                title += warning;
+            }
+
+            if let Some(status) = &self.status {
+                title += &format!(" - {}", status);
+            }
+
+            if let Some(pending) = &self.pending_signal {
+                title += &format!(
+                    " - Send {} to {} ({})? (y/n)",
+                    pending.name,
+                    pending.pid,
+                    pending.cmd.trim()
+                );
+            }
+
             // Create the block
-            let block = Block::default().title(title).borders(Borders::ALL);
+            let mut block = Block::default()
+                .title(labelled_title(self.options.label.as_deref(), &title))
+                .borders(Borders::ALL);
+
+            // Add the selected value status line
+            if stat.proc_stat_type() != ProcStatType::None {
+                if let Some(Ok(value)) = self.selected_proc().map(|p| &p.stat) {
+                    block = block.title(
+                        Title::from(format!(
+                            " {} ",
+                            stat.proc_format().format_exact(*value, self.units)
+                        ))
+                        .position(Position::Bottom),
+                    );
+                }
+            }
 
-            // Draw the table
-            self.table.render(f, block);
+            // Draw the histogram or the table
+            if self.histogram && stat.proc_stat_type() != ProcStatType::None {
+                render_histogram(f, block, self.table.stat_values());
+            } else if self.proc_tree {
+                self.tree.render(f, block);
+            } else {
+                self.table.render(f, block);
+            }
+
+            // Draw the status popup over the processes
+            if let Some(popup) = &self.status_popup {
+                popup.render(f, self.units);
+            }
         })?;
 
         Ok(())
     }
 
     /// Key event
-    #[must_use]
     fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
-        match key_event.code {
-            KeyCode::Char('q')
-            | KeyCode::Esc
-            | KeyCode::Char('p')
-            | KeyCode::Char('t')
-            | KeyCode::Char('P')
-            | KeyCode::Char('T') => Some(vec![Action::Scene(AppScene::CGroupTree)]),
+        // Clear any status message
+        let cleared = self.status.take().is_some();
+
+        // Capture the answer while confirming a signal
+        if self.pending_signal.is_some() {
+            return self.confirm_signal_key(key_event.code);
+        }
+
+        // Capture keys while the status popup is open
+        if self.status_popup.is_some() {
+            return self.status_popup_key(key_event.code);
+        }
+
+        // Capture the filter while in filter input mode
+        if self.filter_input {
+            return self.filter_key(key_event.code);
+        }
+
+        // Translate vim style navigation keys
+        let code = vim_nav_code(key_event, |code| {
+            self.options.keymap.action(code, &KEY_ACTIONS).is_some()
+        });
+
+        let result = match code {
+            KeyCode::Esc if self.filter.is_some() => self.clear_filter(),
+            KeyCode::Esc => Some(vec![Action::Scene(AppScene::CGroupTree)]),
+            KeyCode::Enter => self.open_status_popup(),
+            KeyCode::Up if self.proc_tree => self.tree.up(),
+            KeyCode::Down if self.proc_tree => self.tree.down(),
+            KeyCode::PageUp if self.proc_tree => self.tree.pg_up(),
+            KeyCode::PageDown if self.proc_tree => self.tree.pg_down(),
+            KeyCode::Home if self.proc_tree => self.tree.first(),
+            KeyCode::End if self.proc_tree => self.tree.last(),
+            KeyCode::Left if self.proc_tree => self.tree.left(),
+            KeyCode::Right if self.proc_tree => self.tree.right(),
             KeyCode::Up => self.table.up(),
             KeyCode::Down => self.table.down(),
             KeyCode::PageUp => self.table.pgup(),
             KeyCode::PageDown => self.table.pgdown(),
             KeyCode::Home => self.table.home(),
             KeyCode::End => self.table.end(),
-            KeyCode::Char('i') => self.sort_pid(),
-            KeyCode::Char('n') => self.sort_name(),
-            KeyCode::Char('s') => self.sort_stat(),
-            KeyCode::Char('[') => self.next_stat(false),
-            KeyCode::Char(']') => self.next_stat(true),
-            KeyCode::Char('a') => Some(vec![
-                Action::ProcMode(!self.threads, self.include_children),
-                Action::Reload,
-            ]),
-            KeyCode::Char('c') => Some(vec![
-                Action::ProcMode(self.threads, !self.include_children),
-                Action::Reload,
-            ]),
-            KeyCode::Char('h') => Some(vec![Action::Scene(AppScene::ProcsHelp)]),
-            KeyCode::Char('r') => Some(vec![Action::Reload]),
-            _ => None,
+            code => match self.options.keymap.action(code, &KEY_ACTIONS) {
+                Some(
+                    KeyAction::Quit
+                    | KeyAction::ShowProcs
+                    | KeyAction::ShowThreads
+                    | KeyAction::ShowHierProcs
+                    | KeyAction::ShowHierThreads,
+                ) => Some(vec![Action::Scene(AppScene::CGroupTree)]),
+                Some(KeyAction::SortPid) => self.sort_pid(),
+                Some(KeyAction::SortName) => self.sort_name(),
+                Some(KeyAction::SortStat) => self.sort_stat(),
+                Some(KeyAction::PrevStat) => self.next_stat(false),
+                Some(KeyAction::NextStat) => self.next_stat(true),
+                Some(KeyAction::ToggleThreads) => Some(vec![
+                    Action::ProcMode(!self.threads, self.include_children),
+                    Action::Reload,
+                ]),
+                Some(KeyAction::ToggleChildren) => Some(vec![
+                    Action::ProcMode(self.threads, !self.include_children),
+                    Action::Reload,
+                ]),
+                Some(KeyAction::IncreaseLimit) => self.increase_limit(),
+                Some(KeyAction::DecreaseLimit) => self.decrease_limit(),
+                Some(KeyAction::Help) => Some(vec![Action::Scene(AppScene::ProcsHelp)]),
+                Some(KeyAction::ToggleWatch) => self.toggle_watch(),
+                Some(KeyAction::ToggleComm) => self.toggle_comm(),
+                Some(KeyAction::ToggleContainer) => self.toggle_container(),
+                Some(KeyAction::ToggleHistogram) => self.toggle_histogram(),
+                Some(KeyAction::ToggleUserColours) => self.toggle_user_colours(),
+                Some(KeyAction::ToggleProcTree) => self.toggle_proc_tree(),
+                Some(KeyAction::ProcDetail) => self.proc_detail(),
+                Some(KeyAction::Terminate) => self.request_signal(libc::SIGTERM, "SIGTERM"),
+                Some(KeyAction::Kill) => self.request_signal(libc::SIGKILL, "SIGKILL"),
+                Some(KeyAction::Export) => self.export_visible(),
+                Some(KeyAction::Filter) => self.start_filter(),
+                Some(KeyAction::StateFilter) => self.cycle_state_filter(),
+                Some(KeyAction::Reload) => self.full_reload(),
+                Some(KeyAction::TogglePause) => Some(vec![Action::Pause]),
+                Some(KeyAction::ToggleUnits) => Some(vec![Action::ToggleUnits, Action::Reload]),
+                Some(KeyAction::ToggleRawBytes) => {
+                    Some(vec![Action::ToggleRawBytes, Action::Reload])
+                }
+                Some(KeyAction::Step) if self.paused => Some(vec![Action::Reload]),
+                _ => None,
+            },
+        };
+
+        if result.is_none() && cleared {
+            Some(vec![])
+        } else {
+            result
         }
     }
 
@@ -252,3 +865,30 @@ impl<'a> Scene for ProcsScene<'a> {
         self.next_refresh.checked_duration_since(Instant::now())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::app::keymap::KeyMap;
+
+    #[test]
+    fn default_keys_unique() {
+        let keymap = KeyMap::default();
+        let mut keys = HashSet::new();
+
+        for action in KEY_ACTIONS {
+            assert!(keys.insert(keymap.key(action)), "{:?} shares a key", action);
+        }
+    }
+
+    #[test]
+    fn vim_keys_not_bound_by_default() {
+        let keymap = KeyMap::default();
+
+        for c in ['j', 'k', 'g', 'G'] {
+            assert_eq!(keymap.action(KeyCode::Char(c), &KEY_ACTIONS), None);
+        }
+    }
+}
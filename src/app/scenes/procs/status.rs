@@ -0,0 +1,92 @@
+use std::io::ErrorKind;
+use std::path::Path;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::Frame;
+
+use crate::app::scenes::render_popup;
+use crate::formatters::{format_mem_qty, MemUnits};
+use crate::proc::load_status;
+
+/// Fields shown from the status file, flagged if they are memory sizes in kB
+const FIELDS: [(&str, bool); 6] = [
+    ("State", false),
+    ("PPid", false),
+    ("Threads", false),
+    ("VmSize", true),
+    ("VmRSS", true),
+    ("VmSwap", true),
+];
+
+/// Longest command shown in the popup title
+const MAX_CMD_LEN: usize = 40;
+
+/// Popup over the process list showing fields from the status file of a process
+pub struct ProcStatusPopup {
+    pid: usize,
+    cmd: String,
+    values: Result<Vec<(String, String)>, String>,
+}
+
+impl ProcStatusPopup {
+    /// Creates the popup for a process. The fields are loaded by reload
+    pub fn new(pid: usize, cmd: &str) -> Self {
+        Self {
+            pid,
+            cmd: cmd.trim().chars().take(MAX_CMD_LEN).collect(),
+            values: Ok(Vec::new()),
+        }
+    }
+
+    /// Reloads the status fields
+    pub fn reload(&mut self, proc_root: &Path) {
+        self.values = load_status(proc_root, self.pid).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => "Process gone".into(),
+            _ => format!("Unable to read the process status: {}", e),
+        });
+    }
+
+    pub fn render(&self, frame: &mut Frame, units: MemUnits) {
+        let text = match &self.values {
+            Err(msg) => vec![Line::from(Span::styled(
+                format!(" {}", msg),
+                Style::default().fg(Color::Red),
+            ))],
+            Ok(values) => FIELDS
+                .iter()
+                .map(|(name, memory)| {
+                    let value = values
+                        .iter()
+                        .find(|(key, _)| key == name)
+                        .map(|(_, value)| value.as_str());
+
+                    // Kernel threads have no memory fields
+                    let value = match value {
+                        Some(value) if *memory => value
+                            .strip_suffix(" kB")
+                            .and_then(|kb| kb.trim().parse::<usize>().ok())
+                            .map(|kb| format_mem_qty(kb.saturating_mul(1024), units))
+                            .unwrap_or_else(|| Span::from(value.to_string())),
+                        Some(value) => Span::from(value.to_string()),
+                        None => Span::styled("-", Style::default().add_modifier(Modifier::DIM)),
+                    };
+
+                    Line::from(vec![
+                        Span::styled(
+                            format!(" {:<9}", name),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        value,
+                    ])
+                })
+                .collect(),
+        };
+
+        render_popup(
+            frame,
+            &format!("PID {} {} (Esc to close)", self.pid, self.cmd),
+            text,
+        );
+    }
+}
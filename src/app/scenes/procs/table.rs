@@ -1,25 +1,63 @@
 use std::cmp;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
 
-use ratatui::layout::Constraint;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState};
 use ratatui::Frame;
 
+use crate::app::scenes::render_scrollbar;
 use crate::app::PollResult;
-use crate::cgroup::stats::{ProcStatType, STATS};
+use crate::cgroup::stats::{ProcStatType, Stat, StatFormat};
 use crate::file_proc::FileProcessorError;
-use crate::formatters::format_mem_qty;
-use crate::proc::{load_procs, Proc, ProcSortOrder};
+use crate::formatters::{csv_field, theme, MemUnits};
+use crate::proc::{
+    load_procs,
+    reload_proc_stat,
+    CmdlineCache,
+    Proc,
+    ProcLoadOptions,
+    ProcSortOrder,
+    ProcStateFilter,
+};
+
+/// Colours assigned to process owners by UID
+const USER_COLOURS: [Color; 10] = [
+    Color::LightCyan,
+    Color::LightMagenta,
+    Color::LightYellow,
+    Color::LightGreen,
+    Color::LightBlue,
+    Color::LightRed,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+];
 
 #[derive(Default)]
 pub struct ProcsTable<'a> {
     error: Option<String>,
     procs: Vec<Proc>,
+    rows: Vec<usize>,
+    filter: Option<String>,
+    state_filter: ProcStateFilter,
     header: Row<'a>,
     widths: Vec<Constraint>,
     items: Vec<Row<'a>>,
+    more_row: Option<Row<'a>>,
+    footer: Line<'a>,
+    pid_len: usize,
+    stat_len: Option<usize>,
+    container_len: Option<usize>,
+    limit: Option<usize>,
+    load_options: ProcLoadOptions<'a>,
+    cmdlines: CmdlineCache,
+    units: MemUnits,
+    user_colours: bool,
     state: TableState,
     page_size: u16,
 }
@@ -32,14 +70,23 @@ impl<'a> ProcsTable<'a> {
         cgroup: &Path,
         threads: bool,
         include_children: bool,
-        stat: usize,
+        stat: &Stat,
         sort: ProcSortOrder,
     ) {
         // Get currently selected PID
-        let old_selected_pid = self.selected().map(|i| self.procs[i].pid);
+        let old_selected_pid = self.selected_proc().map(|p| p.pid);
 
         // Load process information
-        match load_procs(cgroup2fs, cgroup, include_children, threads, stat, sort) {
+        match load_procs(
+            cgroup2fs,
+            cgroup,
+            include_children,
+            threads,
+            stat,
+            sort,
+            self.load_options,
+            &mut self.cmdlines,
+        ) {
             Ok(procs) => {
                 self.procs = procs;
                 self.error = None;
@@ -53,16 +100,74 @@ impl<'a> ProcsTable<'a> {
         // Build table cells
         self.build_table_cells(threads, stat, sort);
 
-        // Re-select PID if we had one and it's still there
-        if let Some(old_pid) = old_selected_pid {
-            self.state
-                .select(self.procs.iter().position(|p| p.pid == old_pid));
-        } else {
-            self.state.select(None);
-        }
+        // Re-select PID if we had one and it's still shown
+        self.reselect(old_selected_pid);
+    }
+
+    /// Sets the command filter. Call rebuild to apply it to the loaded processes
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        self.filter = filter;
+    }
+
+    /// Sets the process state filter. Call rebuild to apply it to the loaded processes
+    pub fn set_state_filter(&mut self, state_filter: ProcStateFilter) {
+        self.state_filter = state_filter;
+    }
+
+    /// Rebuilds the table from the loaded processes
+    pub fn rebuild(&mut self, threads: bool, stat: &Stat, sort: ProcSortOrder) {
+        let old_selected_pid = self.selected_proc().map(|p| p.pid);
+
+        self.build_table_cells(threads, stat, sort);
+
+        self.reselect(old_selected_pid);
+    }
+
+    fn reselect(&mut self, old_pid: Option<usize>) {
+        self.state.select(old_pid.and_then(|old_pid| {
+            self.rows
+                .iter()
+                .position(|&i| self.procs[i].pid == old_pid)
+                .filter(|&row| row < self.items.len())
+        }));
+    }
+
+    /// Returns the processes matching the filter
+    fn filtered(&self) -> impl Iterator<Item = &Proc> {
+        self.rows.iter().map(|&i| &self.procs[i])
     }
 
-    fn build_table_cells(&mut self, threads: bool, stat: usize, sort: ProcSortOrder) {
+    fn build_table_cells(&mut self, threads: bool, stat: &Stat, sort: ProcSortOrder) {
+        // Find the processes matching the filters
+        let filter = self.filter.as_ref().map(|f| f.to_lowercase());
+
+        self.rows = self
+            .procs
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| match &filter {
+                Some(filter) => p.cmd.to_lowercase().contains(filter),
+                None => true,
+            })
+            .filter(|(_, p)| self.state_filter.matches(p.state))
+            .map(|(i, _)| i)
+            .collect();
+
+        // Work out how many rows to show
+        let shown = match self.limit {
+            Some(limit) => cmp::min(limit, self.rows.len()),
+            None => self.rows.len(),
+        };
+
+        let more_text = if shown < self.rows.len() {
+            Some(format!(
+                "(+ {} more processes not shown)",
+                self.rows.len() - shown
+            ))
+        } else {
+            None
+        };
+
         let mut header_cells = Vec::new();
         let mut widths = Vec::new();
 
@@ -82,8 +187,7 @@ impl<'a> ProcsTable<'a> {
         // Calculate max PID length
         let pid_len = cmp::max(
             text.chars().count(),
-            self.procs
-                .iter()
+            self.filtered()
                 .map(|p| format!("{}", p.pid).len())
                 .max()
                 .unwrap_or(0),
@@ -92,12 +196,22 @@ impl<'a> ProcsTable<'a> {
         header_cells.push(Cell::from(format!("{:>1$}", text, pid_len)));
         widths.push(Constraint::Length(pid_len as u16));
 
+        // User column
+        let user_len = self
+            .filtered()
+            .map(|p| p.user.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("User".len());
+
+        header_cells.push(Cell::from("User"));
+        widths.push(Constraint::Length(user_len as u16));
+
         // Stat column
-        let mut stat_spans: Vec<Span> = Vec::new();
-        let mut stat_len = 0;
+        let mut stat_len = None;
 
-        if STATS[stat].proc_stat_type() != ProcStatType::None {
-            let mut text: String = STATS[stat].proc_short_desc().into();
+        if stat.proc_stat_type() != ProcStatType::None {
+            let mut text: String = stat.proc_short_desc().into();
 
             match sort {
                 ProcSortOrder::StatAsc => text += " ▼",
@@ -105,32 +219,38 @@ impl<'a> ProcsTable<'a> {
                 _ => (),
             }
 
-            // Calculate stat spans
-            stat_spans = self
-                .procs
-                .iter()
-                .map(|proc| match &proc.stat {
-                    Ok(value) => format_mem_qty(*value),
-                    Err(e) => {
-                        let msg = match e {
-                            FileProcessorError::ValueNotFound => "<None>",
-                            _ => "<Error>",
-                        };
-                        Span::styled(msg, Style::default().fg(Color::Red))
-                    }
-                })
-                .collect();
-
             // Calculate max stat length
-            stat_len = cmp::max(
+            let len = cmp::max(
                 text.chars().count(),
-                stat_spans.iter().map(|s| s.width()).max().unwrap_or(0),
+                self.filtered()
+                    .map(|proc| Self::stat_span(proc, stat.proc_format(), self.units).width())
+                    .max()
+                    .unwrap_or(0),
             );
 
-            header_cells.push(Cell::from(format!("{:>1$}", text, stat_len)));
-            widths.push(Constraint::Length(cmp::max(7, stat_len as u16)));
+            header_cells.push(Cell::from(format!("{:>1$}", text, len)));
+            widths.push(Constraint::Length(cmp::max(7, len as u16)));
+
+            stat_len = Some(len);
         }
 
+        // Container column
+        let container_len = if self.load_options.container {
+            let len = self
+                .filtered()
+                .filter_map(|p| p.container.as_ref().map(|c| c.len()))
+                .max()
+                .unwrap_or(0)
+                .max("Container".len());
+
+            header_cells.push(Cell::from("Container"));
+            widths.push(Constraint::Length(len as u16));
+
+            Some(len)
+        } else {
+            None
+        };
+
         // Command column
         let mut text = "Command".to_string();
 
@@ -142,58 +262,206 @@ impl<'a> ProcsTable<'a> {
 
         // Calculate max command length
         let cmd_len = cmp::max(
-            text.chars().count(),
-            self.procs.iter().map(|p| p.cmd.len()).max().unwrap_or(0),
+            cmp::max(
+                text.chars().count(),
+                more_text.as_ref().map(|t| t.len()).unwrap_or(0),
+            ),
+            self.filtered()
+                .take(shown)
+                .map(|p| p.cmd.len())
+                .max()
+                .unwrap_or(0),
         );
 
         header_cells.push(Cell::from(text));
         widths.push(Constraint::Length(cmd_len as u16));
 
         // Build header
-        let header = Row::new(header_cells)
-            .style(Style::default().bg(Color::Blue))
-            .height(1);
+        let header = Row::new(header_cells).style(theme().header).height(1);
 
         // Build body
         let body_rows = self
-            .procs
-            .iter()
-            .enumerate()
-            .map(|(i, proc)| {
-                let mut cells = Vec::new();
-
-                cells.push(Cell::from(format!("{:>1$}", proc.pid, pid_len)));
+            .filtered()
+            .take(shown)
+            .map(|proc| {
+                Self::build_row(
+                    proc,
+                    pid_len,
+                    stat_len,
+                    container_len.is_some(),
+                    stat.proc_format(),
+                    self.units,
+                    self.user_colours,
+                )
+            })
+            .collect();
 
-                if STATS[stat].proc_stat_type() != ProcStatType::None {
-                    let span = &stat_spans[i];
-                    let pad_len = stat_len - span.width();
-                    let mut spans = Vec::new();
+        // Build row indicating how many processes are not shown
+        let more_row = more_text.map(|text| {
+            let mut cells = vec![Cell::from(""), Cell::from("")];
 
-                    if pad_len > 0 {
-                        spans.push(Span::from(format!("{:>1$}", "", pad_len)))
-                    }
-                    spans.push(span.clone());
+            if stat.proc_stat_type() != ProcStatType::None {
+                cells.push(Cell::from(""));
+            }
 
-                    cells.push(Cell::from(Line::from(spans)));
-                }
+            if container_len.is_some() {
+                cells.push(Cell::from(""));
+            }
 
-                cells.push(Cell::from(proc.cmd.clone()));
+            cells.push(Cell::from(text));
 
-                Row::new(cells)
-            })
-            .collect();
+            Row::new(cells).style(Style::default().add_modifier(Modifier::DIM))
+        });
 
         self.header = header;
         self.widths = widths;
         self.items = body_rows;
+        self.more_row = more_row;
+        self.pid_len = pid_len;
+        self.stat_len = stat_len;
+        self.container_len = container_len;
+
+        self.build_footer(threads, stat);
+    }
+
+    /// Builds the footer line with the number of processes and the statistic total
+    fn build_footer(&mut self, threads: bool, stat: &Stat) {
+        let count = self.rows.len();
+
+        let mut text = format!(
+            " {} {}",
+            count,
+            match (threads, count) {
+                (true, 1) => "thread",
+                (true, _) => "threads",
+                (false, 1) => "process",
+                (false, _) => "processes",
+            }
+        );
+
+        if stat.proc_stat_type() != ProcStatType::None {
+            let (total, _) = self.stat_total();
+
+            text += &format!(
+                ", {} total {}",
+                stat.proc_short_desc(),
+                stat.proc_format().format(total, self.units).content.trim()
+            );
+        }
+
+        self.footer = Line::from(text);
+    }
+
+    #[must_use]
+    pub fn stat_span(proc: &Proc, format: StatFormat, units: MemUnits) -> Span<'static> {
+        match &proc.stat {
+            Ok(value) => format.format(*value, units),
+            Err(e) => {
+                let msg = match e {
+                    FileProcessorError::ValueNotFound => "<None>",
+                    _ => "<Error>",
+                };
+                Span::styled(msg, Style::default().fg(Color::Red))
+            }
+        }
+    }
+
+    #[must_use]
+    fn build_row(
+        proc: &Proc,
+        pid_len: usize,
+        stat_len: Option<usize>,
+        container: bool,
+        format: StatFormat,
+        units: MemUnits,
+        user_colours: bool,
+    ) -> Row<'a> {
+        let mut cells = Vec::new();
+
+        // Colour the user and command by the owning UID if required
+        let user_style = match proc.uid {
+            Some(uid) if user_colours => {
+                Style::default().fg(USER_COLOURS[uid % USER_COLOURS.len()])
+            }
+            _ => Style::default(),
+        };
+
+        cells.push(Cell::from(format!("{:>1$}", proc.pid, pid_len)));
+        cells.push(Cell::from(proc.user.clone()).style(user_style));
+
+        if let Some(stat_len) = stat_len {
+            let span = Self::stat_span(proc, format, units);
+            let pad_len = stat_len.saturating_sub(span.width());
+            let mut spans = Vec::new();
+
+            if pad_len > 0 {
+                spans.push(Span::from(format!("{:>1$}", "", pad_len)))
+            }
+            spans.push(span);
+
+            cells.push(Cell::from(Line::from(spans)));
+        }
+
+        if container {
+            cells.push(Cell::from(proc.container.clone().unwrap_or_default()));
+        }
+
+        cells.push(Cell::from(proc.cmd.clone()).style(user_style));
+
+        Row::new(cells)
+    }
+
+    /// Reloads the statistic for the selected process only. Returns false if there is no
+    /// selection or the process has gone
+    pub fn reload_selected(&mut self, threads: bool, stat: &Stat, sort: ProcSortOrder) -> bool {
+        let Some(row) = self.selected() else {
+            return false;
+        };
+
+        let Some(proc_root) = self.load_options.proc_root else {
+            return false;
+        };
+
+        let i = self.rows[row];
+
+        let Some(value) = reload_proc_stat(proc_root, self.procs[i].pid, stat) else {
+            return false;
+        };
+
+        self.procs[i].stat = value;
+
+        if let Some(stat_len) = self.stat_len {
+            if Self::stat_span(&self.procs[i], stat.proc_format(), self.units).width() > stat_len {
+                // Stat column is now too narrow - rebuild all cells
+                self.build_table_cells(threads, stat, sort);
+                return true;
+            }
+        }
+
+        // Rebuild just this row and the footer total
+        self.build_footer(threads, stat);
+
+        self.items[row] = Self::build_row(
+            &self.procs[i],
+            self.pid_len,
+            self.stat_len,
+            self.container_len.is_some(),
+            stat.proc_format(),
+            self.units,
+            self.user_colours,
+        );
+
+        true
     }
 
     pub fn render(&mut self, frame: &mut Frame, block: Block) {
         // Get the size of the frame
         let size = frame.size();
 
-        // Calculate number of rows in a page
-        self.page_size = std::cmp::max(3, block.inner(size).height) - 2;
+        // Calculate number of rows in a page allowing for the header and footer
+        let inner = block.inner(size);
+        let inner_height = inner.height;
+        self.page_size = std::cmp::max(4, inner_height) - 3;
 
         if let Some(error) = &self.error {
             // Display error message
@@ -205,14 +473,43 @@ impl<'a> ProcsTable<'a> {
             frame.render_widget(para, size);
         } else {
             // Display process table
-            let table = Table::new(self.items.clone())
+            let mut items = self.items.clone();
+
+            if let Some(more_row) = &self.more_row {
+                items.push(more_row.clone());
+            }
+
+            let table = Table::new(items)
                 .header(self.header.clone())
-                .block(block)
-                .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                .highlight_style(theme().highlight)
                 .widths(&self.widths);
 
-            // Draw the table
-            frame.render_stateful_widget(table, size, &mut self.state);
+            // Split the inside of the block between the table and the footer
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(1), Constraint::Length(1)])
+                .split(inner);
+
+            // Draw the block, table and footer
+            frame.render_widget(block, size);
+            frame.render_stateful_widget(table, chunks[0], &mut self.state);
+            frame.render_widget(
+                Paragraph::new(self.footer.clone()).style(theme().header),
+                chunks[1],
+            );
+
+            // Draw the scrollbar between the header and the footer
+            render_scrollbar(
+                frame,
+                Rect {
+                    y: size.y + 1,
+                    height: size.height.saturating_sub(2),
+                    ..size
+                },
+                self.items.len(),
+                inner_height.saturating_sub(2) as usize,
+                self.state.selected().unwrap_or(0),
+            );
         }
     }
 
@@ -259,13 +556,7 @@ impl<'a> ProcsTable<'a> {
                 cmp::min(cur_row + amount as usize, self.items.len() - 1)
             } else {
                 // Moving up
-                let amount = (-amount) as usize;
-
-                if cur_row < amount {
-                    0
-                } else {
-                    cur_row - amount
-                }
+                cur_row.saturating_sub((-amount) as usize)
             };
 
             if cur_row != new_row {
@@ -307,8 +598,178 @@ impl<'a> ProcsTable<'a> {
         self.state = TableState::default();
     }
 
+    /// Exports the processes shown in the table in the displayed order to a CSV file
+    pub fn export_visible_csv(&self, path: &Path, threads: bool, stat: &Stat) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+
+        let has_stat = stat.proc_stat_type() != ProcStatType::None;
+        let container = self.load_options.container;
+
+        // Write the same columns as the table
+        let mut header = vec![
+            if threads { "TID" } else { "PID" }.to_string(),
+            "User".into(),
+        ];
+
+        if has_stat {
+            header.push(csv_field(stat.proc_short_desc()));
+        }
+
+        if container {
+            header.push("Container".into());
+        }
+
+        header.push("Command".into());
+
+        writeln!(file, "{}", header.join(","))?;
+
+        for proc in self.shown_procs() {
+            let mut fields = vec![proc.pid.to_string(), csv_field(&proc.user)];
+
+            if has_stat {
+                fields.push(match &proc.stat {
+                    Ok(value) => value.to_string(),
+                    Err(FileProcessorError::ValueNotFound) => "<None>".to_string(),
+                    Err(_) => "<Error>".to_string(),
+                });
+            }
+
+            if container {
+                fields.push(csv_field(proc.container.as_deref().unwrap_or_default()));
+            }
+
+            fields.push(csv_field(&proc.cmd));
+
+            writeln!(file, "{}", fields.join(","))?;
+        }
+
+        file.flush()
+    }
+
+    /// Sets the maximum number of rows to show
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    /// Sets the units used for memory quantities
+    pub fn set_units(&mut self, units: MemUnits) {
+        self.units = units;
+    }
+
+    /// Sets whether rows are coloured by the owning user
+    pub fn set_user_colours(&mut self, user_colours: bool) {
+        self.user_colours = user_colours;
+    }
+
+    /// Sets the process load options
+    pub fn set_load_options(&mut self, options: ProcLoadOptions<'a>) {
+        self.load_options = options;
+    }
+
+    /// Returns the statistic values of the processes matching the filter, skipping processes
+    /// where the statistic could not be read
+    pub fn stat_values(&self) -> impl Iterator<Item = usize> + '_ {
+        self.filtered()
+            .filter_map(|p| p.stat.as_ref().ok().copied())
+    }
+
+    /// Returns the sum of the statistic and the number of processes it was summed over,
+    /// skipping processes where the statistic could not be read
+    #[must_use]
+    pub fn stat_total(&self) -> (usize, usize) {
+        self.filtered()
+            .filter_map(|p| p.stat.as_ref().ok())
+            .fold((0, 0), |(total, count), value| {
+                (total.saturating_add(*value), count + 1)
+            })
+    }
+
+    /// Returns the total number of processes loaded which match the filter
+    #[must_use]
+    pub fn proc_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the processes shown in the table in the displayed order
+    pub fn shown_procs(&self) -> impl Iterator<Item = &Proc> {
+        self.filtered().take(self.items.len())
+    }
+
+    /// Finds a loaded process by PID
+    #[must_use]
+    pub fn proc_by_pid(&self, pid: usize) -> Option<&Proc> {
+        self.procs.iter().find(|p| p.pid == pid)
+    }
+
+    #[must_use]
+    pub fn selected_proc(&self) -> Option<&Proc> {
+        self.selected().map(|row| &self.procs[self.rows[row]])
+    }
+
     #[must_use]
     pub fn selected(&self) -> Option<usize> {
         self.state.selected()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cgroup::fixture::Fixture;
+    use crate::cgroup::stats::build_stats;
+
+    #[test]
+    fn csv_export_matches_columns() {
+        let stat = build_stats(&[])
+            .into_iter()
+            .find(|stat| stat.proc_stat_type() != ProcStatType::None)
+            .unwrap();
+
+        let mut table = ProcsTable::default();
+
+        table.set_load_options(ProcLoadOptions {
+            container: true,
+            ..Default::default()
+        });
+
+        table.procs = vec![
+            Proc {
+                pid: 10,
+                ppid: None,
+                cmd: "sh -c \"a, b\"".into(),
+                stat: Ok(100),
+                container: Some("abc".into()),
+                uid: Some(1000),
+                user: "first, last".into(),
+                state: None,
+            },
+            Proc {
+                pid: 20,
+                ppid: None,
+                cmd: "init".into(),
+                stat: Err(FileProcessorError::ValueNotFound),
+                container: None,
+                uid: Some(0),
+                user: "root".into(),
+                state: None,
+            },
+        ];
+
+        table.build_table_cells(false, &stat, ProcSortOrder::PidAsc);
+
+        let fixture = Fixture::new(&[]);
+        let path = fixture.path().join("procs.csv");
+
+        table.export_visible_csv(&path, false, &stat).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!(
+                "PID,User,{},Container,Command\n\
+                 10,\"first, last\",100,abc,\"sh -c \"\"a, b\"\"\"\n\
+                 20,root,<None>,,init\n",
+                csv_field(stat.proc_short_desc())
+            )
+        );
+    }
+}
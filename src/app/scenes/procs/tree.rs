@@ -0,0 +1,224 @@
+use std::collections::{HashMap, HashSet};
+
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Block;
+use ratatui::Frame;
+use tui_tree_widget::{flatten, Tree, TreeItem, TreeState};
+
+use super::table::ProcsTable;
+use crate::app::scenes::render_scrollbar;
+use crate::app::PollResult;
+use crate::cgroup::stats::{ProcStatType, Stat};
+use crate::formatters::{theme, MemUnits};
+use crate::proc::Proc;
+
+/// Processes arranged by parent PID. Nodes are identified by PID
+#[derive(Default)]
+pub struct ProcsTree<'a> {
+    items: Vec<TreeItem<'a, usize>>,
+    state: TreeState<usize>,
+    parents: HashSet<Vec<usize>>,
+    page_size: u16,
+}
+
+impl<'a> ProcsTree<'a> {
+    /// Builds the tree from the processes in display order. Processes whose parent is not in
+    /// the list are placed at the top level
+    pub fn build_tree(&mut self, procs: &[&Proc], stat: &Stat, units: MemUnits) {
+        let old_selected_pid = self.selected_pid();
+
+        // Find the children of each process, ignoring duplicate PIDs
+        let mut pids = HashSet::new();
+        let procs: Vec<&Proc> = procs
+            .iter()
+            .filter(|proc| pids.insert(proc.pid))
+            .copied()
+            .collect();
+
+        let mut roots = Vec::new();
+        let mut children: HashMap<usize, Vec<&Proc>> = HashMap::new();
+
+        for proc in procs {
+            match proc.ppid {
+                Some(ppid) if ppid != proc.pid && pids.contains(&ppid) => {
+                    children.entry(ppid).or_default().push(proc)
+                }
+                _ => roots.push(proc),
+            }
+        }
+
+        let mut paths = HashMap::new();
+
+        self.items = self.build_tree_level(&roots, &children, stat, units, Vec::new(), &mut paths);
+
+        // Re-select the previously selected process wherever it now is in the tree
+        match old_selected_pid.and_then(|pid| paths.remove(&pid)) {
+            Some(path) => self.state.select(path),
+            None => self.state.select(vec![]),
+        }
+    }
+
+    fn build_tree_level(
+        &mut self,
+        procs: &[&Proc],
+        children: &HashMap<usize, Vec<&Proc>>,
+        stat: &Stat,
+        units: MemUnits,
+        cur_item: Vec<usize>,
+        paths: &mut HashMap<usize, Vec<usize>>,
+    ) -> Vec<TreeItem<'a, usize>> {
+        procs
+            .iter()
+            .map(|proc| {
+                let mut next = cur_item.clone();
+                next.push(proc.pid);
+
+                let sub_nodes = match children.get(&proc.pid) {
+                    Some(sub_procs) => {
+                        self.build_tree_level(sub_procs, children, stat, units, next.clone(), paths)
+                    }
+                    None => Vec::new(),
+                };
+
+                // Expand parents the first time they are seen, leaving any collapsed since
+                if !sub_nodes.is_empty() && self.parents.insert(next.clone()) {
+                    self.state.open(next.clone());
+                }
+
+                paths.insert(proc.pid, next);
+
+                TreeItem::new(proc.pid, Self::proc_line(proc, stat, units), sub_nodes).unwrap()
+            })
+            .collect()
+    }
+
+    fn proc_line(proc: &Proc, stat: &Stat, units: MemUnits) -> Line<'static> {
+        let mut spans = vec![Span::from(format!("{} {} ", proc.pid, proc.user))];
+
+        if stat.proc_stat_type() != ProcStatType::None {
+            spans.push(ProcsTable::stat_span(proc, stat.proc_format(), units));
+            spans.push(Span::from(" "));
+        }
+
+        spans.push(Span::from(proc.cmd.clone()));
+
+        Line::from(spans)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, block: Block) {
+        // Get the size of the frame
+        let size = frame.size();
+
+        // Calculate number of rows in a page
+        let inner_height = block.inner(size).height;
+        self.page_size = std::cmp::max(2, inner_height) - 1;
+
+        // Create the tree
+        let tree = Tree::new(self.items.clone())
+            .unwrap()
+            .block(block)
+            .highlight_style(theme().highlight);
+
+        // Draw the tree
+        frame.render_stateful_widget(tree, size, &mut self.state);
+
+        // Draw the scrollbar
+        let visible = flatten(&self.state.get_all_opened(), &self.items);
+        let selected = self.state.selected();
+
+        render_scrollbar(
+            frame,
+            size,
+            visible.len(),
+            inner_height as usize,
+            visible
+                .iter()
+                .position(|v| v.identifier == selected)
+                .unwrap_or(0),
+        );
+    }
+
+    fn move_by(&mut self, amount: isize, no_pos: isize) -> PollResult {
+        let visible = flatten(&self.state.get_all_opened(), &self.items);
+
+        if visible.is_empty() {
+            return None;
+        }
+
+        let current_identifier = self.state.selected();
+
+        let current_index = visible
+            .iter()
+            .position(|o| o.identifier == current_identifier);
+
+        let new_index = match current_index {
+            Some(idx) => idx as isize + amount,
+            None => no_pos + amount,
+        }
+        .max(0)
+        .min(visible.len() as isize - 1) as usize;
+
+        if Some(new_index) != current_index {
+            let new_identifier = visible[new_index].identifier.clone();
+            self.state.select(new_identifier);
+            Some(vec![])
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn left(&mut self) -> PollResult {
+        self.state.key_left();
+        Some(vec![])
+    }
+
+    #[must_use]
+    pub fn right(&mut self) -> PollResult {
+        self.state.key_right();
+        Some(vec![])
+    }
+
+    #[must_use]
+    pub fn down(&mut self) -> PollResult {
+        self.move_by(1, -1)
+    }
+
+    #[must_use]
+    pub fn up(&mut self) -> PollResult {
+        self.move_by(-1, self.page_size as isize + 1)
+    }
+
+    #[must_use]
+    pub fn pg_down(&mut self) -> PollResult {
+        self.move_by(self.page_size as isize, 0)
+    }
+
+    #[must_use]
+    pub fn pg_up(&mut self) -> PollResult {
+        self.move_by(-(self.page_size as isize), self.page_size as isize)
+    }
+
+    #[must_use]
+    pub fn first(&mut self) -> PollResult {
+        self.state.select_first(&self.items);
+        Some(vec![])
+    }
+
+    #[must_use]
+    pub fn last(&mut self) -> PollResult {
+        self.state.select_last(&self.items);
+        Some(vec![])
+    }
+
+    /// Returns the PID of the selected process
+    #[must_use]
+    pub fn selected_pid(&self) -> Option<usize> {
+        self.state.selected().last().copied()
+    }
+
+    pub fn reset(&mut self) {
+        self.state = TreeState::default();
+        self.parents.clear();
+    }
+}
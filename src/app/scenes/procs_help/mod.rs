@@ -1,7 +1,10 @@
 use super::help::HelpScene;
+use crate::app::keymap::KeyAction;
+use crate::app::AppOptions;
 
-pub fn build_procs_help_scene<'a>() -> HelpScene<'a> {
-    let mut help = HelpScene::new();
+pub fn build_procs_help_scene(options: &AppOptions) -> HelpScene<'_> {
+    let keymap = &options.keymap;
+    let mut help = HelpScene::new(options.label.as_deref(), keymap);
 
     // Create the help text
     help.add_line("Key bindings for process display:");
@@ -13,24 +16,122 @@ pub fn build_procs_help_scene<'a>() -> HelpScene<'a> {
     help.add_key("Page Down", "Move selection down a page.");
     help.add_key("Home", "Move selection to the top.");
     help.add_key("End", "Move selection to the end.");
-    help.add_key("a", "Toggle between processes and threads.");
-    help.add_key("c", "Toggle child cgroup processes/threads.");
     help.add_key(
-        "n",
+        "j / k / g / G",
+        "Vim style aliases for Down, Up, Home and End, unless the key is bound to an action in the configuration file.",
+    );
+    help.add_key("Ctrl-d / Ctrl-u", "Move selection down / up a page.");
+    help.add_key(
+        keymap.key(KeyAction::ToggleThreads),
+        "Toggle between processes and threads.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleChildren),
+        "Toggle child cgroup processes/threads.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::SortPid),
+        "Sort by PID. Pressing again toggles ascending / descending sort order.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::SortName),
         "Sort by command. Pressing again toggles ascending / descending sort order.",
     );
     help.add_key(
-        "s",
+        keymap.key(KeyAction::SortStat),
         "Sort by memory usage / PID. Pressing again toggles ascending / descending sort order.",
     );
-    help.add_key("[", "Move to previous statistic.");
-    help.add_key("]", "Move to next statistic.");
-    help.add_key("r", "Refresh the list.");
-    help.add_key("h", "Shows this help screen.");
-    help.add_key("Esc / q", "Exit the window.");
+    help.add_key(
+        keymap.key(KeyAction::IncreaseLimit),
+        "Increase the number of processes shown when limited.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::DecreaseLimit),
+        "Limit the number of processes shown, or decrease the limit.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleComm),
+        "Toggle showing the process name (comm) in preference to the command line.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleContainer),
+        "Toggle a column showing the container ID found in each process's cgroup path.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleHistogram),
+        "Toggle a histogram of the statistic values in place of the table.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleUserColours),
+        "Toggle colouring processes by their owning user.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleProcTree),
+        "Toggle showing the processes as a tree by parent process. Left and Right collapse and expand.",
+    );
+    help.add_key(
+        "Enter",
+        "Show the state, parent PID, thread count and memory sizes of the selected process in a popup. Esc closes it.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ProcDetail),
+        "Show PSS, USS and shared memory for the selected process from smaps_rollup.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::Filter),
+        "Filter the processes by command. Enter finishes the filter and Esc clears it.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::StateFilter),
+        "Cycle between showing all processes or only those running, sleeping, in disk sleep, stopped or zombie.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::PrevStat),
+        "Move to previous statistic.",
+    );
+    help.add_key(keymap.key(KeyAction::NextStat), "Move to next statistic.");
+    help.add_key(
+        keymap.key(KeyAction::ToggleWatch),
+        "Toggle watching the selected process. Automatic refreshes only update its row.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::Export),
+        "Export the shown processes to a CSV file in the current directory.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::Terminate),
+        "Send SIGTERM to the selected process after confirmation.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::Kill),
+        "Send SIGKILL to the selected process after confirmation.",
+    );
+    help.add_key(keymap.key(KeyAction::Reload), "Refresh the list.");
+    help.add_key(
+        keymap.key(KeyAction::ToggleUnits),
+        "Toggle memory units between binary (KiB, MiB) and decimal (kB, MB).",
+    );
+    help.add_key(
+        keymap.key(KeyAction::ToggleRawBytes),
+        "Toggle showing memory quantities as exact byte counts.",
+    );
+    help.add_key(
+        keymap.key(KeyAction::TogglePause),
+        "Pause or resume automatic refreshes.",
+    );
+    help.add_key(keymap.key(KeyAction::Step), "Refresh once while paused.");
+    help.add_key(keymap.key(KeyAction::Help), "Shows this help screen.");
+    help.add_key(
+        format!("Esc / {}", keymap.key(KeyAction::Quit)),
+        "Exit the window.",
+    );
 
     help.add_line("");
-    help.add_line("Press q, h or Esc to exit help");
+    help.add_line(format!(
+        "Press {}, {} or Esc to exit help",
+        keymap.key(KeyAction::Quit),
+        keymap.key(KeyAction::Help)
+    ));
 
     help
 }
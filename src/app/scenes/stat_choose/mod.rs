@@ -5,41 +5,109 @@ use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
 
-use super::Scene;
-use crate::app::{Action, AppScene, PollResult};
-use crate::cgroup::stats::STATS;
+use super::{labelled_title, vim_nav_code, Scene};
+use crate::app::{Action, AppOptions, AppScene, PollResult};
+use crate::cgroup::stats::{Stat, StatType};
+use crate::formatters::theme;
 use crate::TermType;
 
+/// Order of the statistics in the chooser
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StatChooseSort {
+    Definition,
+    Alphabetical,
+    Type,
+}
+
 pub struct StatChooseScene<'a> {
+    label: Option<&'a str>,
+    stats: &'a [Stat<'a>],
+    sort: StatChooseSort,
+    order: Vec<usize>,
     items: Vec<ListItem<'a>>,
     state: ListState,
+    page_size: usize,
+    number: Option<usize>,
 }
 
 impl<'a> StatChooseScene<'a> {
-    pub fn new() -> Self {
-        // Build list items
-        let items = STATS
+    pub fn new(stats: &'a [Stat<'a>], options: &'a AppOptions) -> Self {
+        let mut scene = Self {
+            label: options.label.as_deref(),
+            stats,
+            sort: StatChooseSort::Definition,
+            order: Vec::new(),
+            items: Vec::new(),
+            state: ListState::default(),
+            page_size: 1,
+            number: None,
+        };
+
+        scene.build_items();
+
+        scene
+    }
+
+    pub fn set_stat(&mut self, stat: usize) {
+        self.number = None;
+        self.state
+            .select(self.order.iter().position(|&item| item == stat));
+    }
+
+    /// Builds the list items in the current sort order
+    fn build_items(&mut self) {
+        // Process only statistics can't be shown in the tree
+        self.order = (0..self.stats.len())
+            .filter(|&i| !self.stats[i].proc_only())
+            .collect();
+
+        match self.sort {
+            StatChooseSort::Definition => (),
+            StatChooseSort::Alphabetical => self
+                .order
+                .sort_by_key(|&i| self.stats[i].desc().to_lowercase()),
+            StatChooseSort::Type => self
+                .order
+                .sort_by_key(|&i| match self.stats[i].stat_type() {
+                    StatType::MemQtyCumul | StatType::MemQty => 0,
+                    StatType::TimeCumul => 1,
+                    StatType::Qty => 2,
+                    StatType::Pct => 3,
+                }),
+        }
+
+        self.items = self
+            .order
             .iter()
-            .enumerate()
-            .map(|(i, stat)| {
+            .map(|&i| {
                 ListItem::new(Line::from(vec![
                     Span::styled(
                         format!(" {:>2} ", i + 1),
                         Style::default().add_modifier(Modifier::DIM),
                     ),
-                    Span::from(stat.desc()),
+                    Span::from(self.stats[i].desc()),
                 ]))
             })
             .collect();
+    }
 
-        Self {
-            items,
-            state: ListState::default(),
+    #[must_use]
+    fn cycle_sort(&mut self) -> PollResult {
+        let selected = self.state.selected().map(|pos| self.order[pos]);
+
+        self.sort = match self.sort {
+            StatChooseSort::Definition => StatChooseSort::Alphabetical,
+            StatChooseSort::Alphabetical => StatChooseSort::Type,
+            StatChooseSort::Type => StatChooseSort::Definition,
+        };
+
+        self.build_items();
+
+        if let Some(stat) = selected {
+            self.set_stat(stat);
         }
-    }
 
-    pub fn set_stat(&mut self, stat: usize) {
-        self.state.select(Some(stat));
+        Some(vec![])
     }
 
     #[must_use]
@@ -72,11 +140,76 @@ impl<'a> StatChooseScene<'a> {
         }
     }
 
+    #[must_use]
+    fn move_to(&mut self, pos: isize) -> PollResult {
+        let pos = pos.clamp(0, self.items.len() as isize - 1) as usize;
+
+        if self.state.selected() == Some(pos) {
+            None
+        } else {
+            self.state.select(Some(pos));
+            Some(vec![])
+        }
+    }
+
+    #[must_use]
+    fn move_by(&mut self, amount: isize) -> PollResult {
+        let pos = self.state.selected().unwrap_or(0) as isize + amount;
+
+        self.move_to(pos)
+    }
+
+    /// Returns the list position of the statistic with the given displayed number
+    fn number_pos(&self, number: usize) -> Option<usize> {
+        self.order.iter().position(|&i| i + 1 == number)
+    }
+
+    /// Tests if typing more digits after a number could give a displayed statistic number
+    fn number_extends(&self, number: usize) -> bool {
+        self.order.iter().any(|&i| {
+            let mut n = i + 1;
+
+            while n >= 10 {
+                n /= 10;
+
+                if n == number {
+                    return true;
+                }
+            }
+
+            false
+        })
+    }
+
+    /// Adds a digit to the typed statistic number and moves to that statistic. The statistic is
+    /// selected straight away if no more digits could follow. Digits giving a number which
+    /// isn't shown are ignored
+    #[must_use]
+    fn number_key(&mut self, digit: usize) -> PollResult {
+        let number = self.number.unwrap_or(0) * 10 + digit;
+
+        let pos = self.number_pos(number)?;
+
+        self.state.select(Some(pos));
+
+        if self.number_extends(number) {
+            self.number = Some(number);
+            Some(vec![])
+        } else {
+            self.select()
+        }
+    }
+
     #[must_use]
     fn select(&mut self) -> PollResult {
-        self.state
-            .selected()
-            .map(|selected| vec![Action::Stat(selected), Action::Scene(AppScene::CGroupTree)])
+        self.number = None;
+
+        self.state.selected().map(|selected| {
+            vec![
+                Action::Stat(self.order[selected]),
+                Action::Scene(AppScene::CGroupTree),
+            ]
+        })
     }
 }
 
@@ -90,15 +223,34 @@ impl<'a> Scene for StatChooseScene<'a> {
             // Get the size of the frame
             let size = f.size();
 
+            self.page_size = std::cmp::max(3, size.height as usize) - 2;
+
             // Create the block
+            let sort_desc = match self.sort {
+                StatChooseSort::Definition => "",
+                StatChooseSort::Alphabetical => " by Name",
+                StatChooseSort::Type => " by Type",
+            };
+
             let block = Block::default()
-                .title("Displayed Statistic")
+                .title(labelled_title(
+                    self.label,
+                    &match self.number {
+                        Some(number) => format!(
+                            "Displayed Statistic{} (press o to change order) (number: {}_)",
+                            sort_desc, number
+                        ),
+                        None => {
+                            format!("Displayed Statistic{} (press o to change order)", sort_desc)
+                        }
+                    },
+                ))
                 .borders(Borders::ALL);
 
             // Create the list
             let list = List::new(self.items.clone())
                 .block(block)
-                .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+                .highlight_style(theme().highlight);
 
             // Draw the paragraph
             f.render_stateful_widget(list, size, &mut self.state);
@@ -108,15 +260,30 @@ impl<'a> Scene for StatChooseScene<'a> {
     }
 
     /// Key events
-    #[must_use]
     fn key_event(&mut self, key_event: KeyEvent) -> PollResult {
-        match key_event.code {
+        // Digits type a statistic number, anything else clears it
+        let code = vim_nav_code(key_event, |_| false);
+
+        if let KeyCode::Char(c) = code {
+            if let Some(digit) = c.to_digit(10) {
+                return self.number_key(digit as usize);
+            }
+        }
+
+        self.number = None;
+
+        match code {
             KeyCode::Char('q') | KeyCode::Char('h') | KeyCode::Esc => {
                 Some(vec![Action::Scene(AppScene::CGroupTree)])
             }
             KeyCode::Down => self.down(),
             KeyCode::Up => self.up(),
+            KeyCode::PageDown => self.move_by(self.page_size as isize),
+            KeyCode::PageUp => self.move_by(-(self.page_size as isize)),
+            KeyCode::Home => self.move_to(0),
+            KeyCode::End => self.move_to(isize::MAX),
             KeyCode::Enter | KeyCode::Char(' ') => self.select(),
+            KeyCode::Char('o') => self.cycle_sort(),
             _ => None,
         }
     }
@@ -0,0 +1,44 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{fs, process};
+
+/// Number of fixtures created by this process, used to make the directory names unique
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Directory of cgroup files for tests, removed when dropped
+pub struct Fixture {
+    path: PathBuf,
+}
+
+impl Fixture {
+    /// Creates a fixture from a list of relative file paths and their contents. Parent
+    /// directories are created as needed
+    pub fn new(files: &[(&str, &str)]) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "cgroup_mem_test_{}_{}",
+            process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        fs::create_dir_all(&path).unwrap();
+
+        for (file, contents) in files {
+            let file = path.join(file);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, contents).unwrap();
+        }
+
+        Self { path }
+    }
+
+    /// Returns the path of the fixture directory
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
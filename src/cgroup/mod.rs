@@ -1,17 +1,41 @@
 pub mod stats;
 
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+#[cfg(test)]
+pub mod fixture;
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use self::stats::{Stat, StatType};
+use crate::file_proc::{get_file_processor, FileProcessor, FileProcessorError, KeyedProcessor};
+use crate::formatters::json_string;
 
-use self::stats::{StatType, STATS};
-use crate::file_proc::{get_file_processor, FileProcessor, KeyedProcessor};
+/// Flags read from cgroup.events
+#[derive(Debug, Clone, Copy)]
+pub struct CGroupEventFlags {
+    pub populated: bool,
+    pub frozen: bool,
+}
 
 #[derive(Debug, Clone)]
 pub struct CGroup {
     path: PathBuf,
     error: Option<String>,
     stat: usize,
+    high_events: Option<usize>,
+    event_flags: Option<CGroupEventFlags>,
+    oom_group: bool,
+    task_counts: Option<(usize, usize)>,
+    proc_count: Option<usize>,
+    inode: Option<u64>,
+    usage: Option<usize>,
+    io_bytes: Option<(usize, usize)>,
+    subtree_control: Option<Vec<String>>,
+    populated: bool,
     children: Vec<CGroup>,
 }
 
@@ -21,6 +45,16 @@ impl CGroup {
             path,
             error: None,
             stat: 0,
+            high_events: None,
+            event_flags: None,
+            oom_group: false,
+            task_counts: None,
+            proc_count: None,
+            inode: None,
+            usage: None,
+            io_bytes: None,
+            subtree_control: None,
+            populated: false,
             children: Vec::new(),
         }
     }
@@ -30,6 +64,16 @@ impl CGroup {
             path,
             error: Some(msg),
             stat: 0,
+            high_events: None,
+            event_flags: None,
+            oom_group: false,
+            task_counts: None,
+            proc_count: None,
+            inode: None,
+            usage: None,
+            io_bytes: None,
+            subtree_control: None,
+            populated: false,
             children: Vec::new(),
         }
     }
@@ -42,16 +86,88 @@ impl CGroup {
         self.stat
     }
 
+    pub fn high_events(&self) -> Option<usize> {
+        self.high_events
+    }
+
+    /// Populated and frozen flags. None for the root cgroup which has no cgroup.events
+    pub fn event_flags(&self) -> Option<CGroupEventFlags> {
+        self.event_flags
+    }
+
+    pub fn oom_group(&self) -> bool {
+        self.oom_group
+    }
+
+    /// Hierarchical process and thread counts
+    pub fn task_counts(&self) -> Option<(usize, usize)> {
+        self.task_counts
+    }
+
+    /// Number of processes including descendents
+    pub fn proc_count(&self) -> Option<usize> {
+        self.proc_count
+    }
+
+    /// Inode number of the cgroup directory
+    pub fn inode(&self) -> Option<u64> {
+        self.inode
+    }
+
+    /// Current memory usage including descendents
+    pub fn usage(&self) -> Option<usize> {
+        self.usage
+    }
+
+    /// Total bytes read and written including descendents
+    pub fn io_bytes(&self) -> Option<(usize, usize)> {
+        self.io_bytes
+    }
+
+    pub fn subtree_control(&self) -> Option<&Vec<String>> {
+        self.subtree_control.as_ref()
+    }
+
     pub fn children(&self) -> &Vec<CGroup> {
         &self.children
     }
 
+    pub fn children_mut(&mut self) -> &mut Vec<CGroup> {
+        &mut self.children
+    }
+
     pub fn error(&self) -> &Option<String> {
         &self.error
     }
+
+    /// Writes the cgroup and its descendents as a JSON object
+    pub fn write_json<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(
+            w,
+            "{{\"path\":{},",
+            json_string(&format!("/{}", self.path.to_string_lossy()))
+        )?;
+
+        match &self.error {
+            Some(msg) => write!(w, "\"error\":{},", json_string(msg))?,
+            None => write!(w, "\"stat\":{},", self.stat)?,
+        }
+
+        write!(w, "\"children\":[")?;
+
+        for (i, child) in self.children.iter().enumerate() {
+            if i > 0 {
+                write!(w, ",")?;
+            }
+
+            child.write_json(w)?;
+        }
+
+        write!(w, "]}}")
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CGroupSortOrder {
     NameAsc,
     NameDsc,
@@ -59,12 +175,117 @@ pub enum CGroupSortOrder {
     StatDsc,
 }
 
-pub fn load_cgroups(cgroup2fs: &Path, stat: usize, sort: CGroupSortOrder) -> Vec<CGroup> {
-    let rel_path = PathBuf::new();
+impl CGroupSortOrder {
+    /// Returns the name of the sort order used in the state file and on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            CGroupSortOrder::NameAsc => "name-asc",
+            CGroupSortOrder::NameDsc => "name-desc",
+            CGroupSortOrder::StatAsc => "stat-asc",
+            CGroupSortOrder::StatDsc => "stat-desc",
+        }
+    }
+
+    /// Finds a sort order by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "name-asc" => Some(CGroupSortOrder::NameAsc),
+            "name-desc" => Some(CGroupSortOrder::NameDsc),
+            "stat-asc" => Some(CGroupSortOrder::StatAsc),
+            "stat-desc" => Some(CGroupSortOrder::StatDsc),
+            _ => None,
+        }
+    }
+}
+
+/// Optional extra information to load for each cgroup
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub high_events: bool,
+    pub event_flags: bool,
+    pub oom_group: bool,
+    pub task_counts: bool,
+    pub proc_average: bool,
+    pub inode: bool,
+    pub populated_only: bool,
+    pub usage: bool,
+    pub io_bytes: bool,
+    pub subtree_control: bool,
+}
+
+struct LoadContext {
+    sort: CGroupSortOrder,
+    stat_type: StatType,
+    self_node: bool,
+    options: LoadOptions,
+    processor: Box<dyn FileProcessor>,
+    high_processor: Box<dyn FileProcessor>,
+    oom_group_processor: Box<dyn FileProcessor>,
+    usage_processor: Box<dyn FileProcessor>,
+    procs_processor: Box<dyn FileProcessor>,
+    threads_processor: Box<dyn FileProcessor>,
+    spare_threads: AtomicUsize,
+}
+
+impl LoadContext {
+    fn new(
+        processor: Box<dyn FileProcessor>,
+        stat_type: StatType,
+        self_node: bool,
+        sort: CGroupSortOrder,
+        options: LoadOptions,
+    ) -> Self {
+        Self {
+            sort,
+            stat_type,
+            self_node,
+            options,
+            processor,
+            high_processor: get_file_processor("memory.events/=/1/high/2").unwrap(),
+            oom_group_processor: get_file_processor("memory.oom.group").unwrap(),
+            usage_processor: get_file_processor("memory.current").unwrap(),
+            procs_processor: get_file_processor("cgroup.procs/#").unwrap(),
+            threads_processor: get_file_processor("cgroup.threads/#").unwrap(),
+            spare_threads: AtomicUsize::new(
+                thread::available_parallelism().map_or(0, |n| n.get().saturating_sub(1)),
+            ),
+        }
+    }
+
+    /// Reserves a spare worker thread. Returns false if none are available
+    fn take_thread(&self) -> bool {
+        self.spare_threads
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Returns a worker thread to the spare pool
+    fn release_thread(&self) {
+        self.spare_threads.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// Loads the cgroup hierarchy under root with the values of a statistic. Returns an error if
+/// the statistic has no cgroup file
+pub fn load_cgroups(
+    cgroup2fs: &Path,
+    root: &Path,
+    stat: &Stat,
+    sort: CGroupSortOrder,
+    options: LoadOptions,
+) -> io::Result<Vec<CGroup>> {
+    let rel_path = root.to_path_buf();
+
+    let processor = stat.def().and_then(get_file_processor).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no cgroup file", stat.short_desc()),
+        )
+    })?;
 
-    let processor = get_file_processor(STATS[stat].def()).unwrap();
+    let ctx = LoadContext::new(processor, stat.stat_type(), stat.self_node(), sort, options);
 
-    match load_cgroup_rec(cgroup2fs.to_path_buf(), &rel_path, sort, stat, &*processor) {
+    let cgroups = match load_cgroup_rec(cgroup2fs.join(root), &rel_path, &ctx) {
         Ok(cgroup) => {
             if cgroup.error.is_some() && !cgroup.children.is_empty() {
                 // Handle case where this is no file in the root directory
@@ -74,44 +295,30 @@ pub fn load_cgroups(cgroup2fs: &Path, stat: usize, sort: CGroupSortOrder) -> Vec
             }
         }
         Err(e) => vec![CGroup::new_error(rel_path, e.to_string())],
-    }
+    };
+
+    Ok(cgroups)
 }
 
-fn load_cgroup_rec(
-    abs_path: PathBuf,
-    rel_path: &Path,
-    sort: CGroupSortOrder,
-    stat: usize,
-    processor: &dyn FileProcessor,
-) -> io::Result<CGroup> {
+fn load_cgroup_rec(abs_path: PathBuf, rel_path: &Path, ctx: &LoadContext) -> io::Result<CGroup> {
     let mut cgroup = CGroup::new(rel_path.to_path_buf());
 
-    // Recurse in to sub directories first
-    let dir = abs_path.read_dir()?;
-
-    dir.for_each(|file| {
-        if let Ok(file) = file {
-            let fname = file.file_name();
+    // Find the sub directories
+    let dirs: Vec<(PathBuf, PathBuf)> = abs_path
+        .read_dir()?
+        .flatten()
+        .filter(|file| file.file_type().is_ok_and(|ftype| ftype.is_dir()))
+        .map(|file| (file.path(), rel_path.join(file.file_name())))
+        .collect();
 
-            if let Ok(ftype) = file.file_type() {
-                if ftype.is_dir() {
-                    let mut sub_rel_path = rel_path.to_path_buf();
-                    sub_rel_path.push(fname);
-
-                    match load_cgroup_rec(file.path(), &sub_rel_path, sort, stat, processor) {
-                        Ok(sub_cgroup) => cgroup.children.push(sub_cgroup),
-                        Err(e) => cgroup
-                            .children
-                            .push(CGroup::new_error(sub_rel_path, e.to_string())),
-                    }
-                }
-            }
-        }
-    });
+    // Recurse in to sub directories first
+    cgroup.children = load_children(dirs, ctx);
 
     // Get the statistic for this cgroup
-    match processor.get_stat(&abs_path) {
+    match ctx.processor.get_stat(&abs_path) {
         Ok(stat) => cgroup.stat = stat,
+        // A missing key (eg. an unpopulated memory.stat entry) counts as zero
+        Err(FileProcessorError::ValueNotFound) => cgroup.stat = 0,
         Err(e) => {
             cgroup.error = Some(e.to_string());
 
@@ -123,10 +330,80 @@ fn load_cgroup_rec(
         }
     }
 
-    match STATS[stat].stat_type() {
-        StatType::Qty => {
+    // Get the memory.high event count
+    if ctx.options.high_events {
+        cgroup.high_events = ctx.high_processor.get_stat(&abs_path).ok();
+    }
+
+    // Get the populated and frozen flags
+    if ctx.options.event_flags {
+        cgroup.event_flags = read_event_flags(&abs_path).ok();
+    }
+
+    // Get the OOM group setting
+    if ctx.options.oom_group {
+        cgroup.oom_group = matches!(ctx.oom_group_processor.get_stat(&abs_path), Ok(v) if v != 0);
+    }
+
+    // Get the current memory usage
+    if ctx.options.usage {
+        cgroup.usage = ctx.usage_processor.get_stat(&abs_path).ok();
+    }
+
+    // Get the total bytes read and written
+    if ctx.options.io_bytes {
+        cgroup.io_bytes = read_io_stat(&abs_path).ok();
+    }
+
+    // Get the controllers enabled for children
+    if ctx.options.subtree_control {
+        cgroup.subtree_control = read_controllers(&abs_path, "cgroup.subtree_control").ok();
+    }
+
+    // Get the directory inode number
+    if ctx.options.inode {
+        cgroup.inode = abs_path.metadata().ok().map(|m| m.ino());
+    }
+
+    // Check if this cgroup or any descendent has processes
+    if ctx.options.populated_only {
+        cgroup.populated = cgroup.children.iter().any(|c| c.populated)
+            || matches!(ctx.procs_processor.get_stat(&abs_path), Ok(procs) if procs > 0);
+    }
+
+    // Get the process and thread counts including descendents
+    if ctx.options.task_counts {
+        let procs = ctx.procs_processor.get_stat(&abs_path).unwrap_or(0);
+        let threads = ctx.threads_processor.get_stat(&abs_path).unwrap_or(0);
+
+        cgroup.task_counts = Some(
+            cgroup
+                .children
+                .iter()
+                .filter_map(|c| c.task_counts)
+                .fold((procs, threads), |(p, t), (cp, ct)| {
+                    (p.saturating_add(cp), t.saturating_add(ct))
+                }),
+        );
+    }
+
+    // Get the process count including descendents for the average per process
+    if ctx.options.proc_average {
+        let procs = ctx.procs_processor.get_stat(&abs_path).unwrap_or(0);
+
+        cgroup.proc_count = Some(
+            cgroup
+                .children
+                .iter()
+                .filter_map(|c| c.proc_count)
+                .fold(procs, usize::saturating_add),
+        );
+    }
+
+    match ctx.stat_type {
+        StatType::Qty | StatType::MemQty => {
             // Non-cumulative quantity
-            let child_sum: usize = cgroup.children.iter().map(|c| c.stat).sum();
+            let child_sum = sum_stats(&cgroup.children);
 
             if child_sum > 0 {
                 if cgroup.stat > 0 {
@@ -138,16 +415,20 @@ fn load_cgroup_rec(
                     cgroup.children.push(cg_self);
                 }
 
-                cgroup.stat += child_sum;
+                cgroup.stat = cgroup.stat.saturating_add(child_sum);
             }
         }
-        StatType::MemQtyCumul => {
+        StatType::Pct => {
+            // Percentages can't be combined
+        }
+        StatType::MemQtyCumul | StatType::TimeCumul => {
             // Cumulative quantity
-            if !cgroup.children.is_empty() {
+            if ctx.self_node && !cgroup.children.is_empty() {
                 // Add a <self> node for difference in memory between the sum of the children and this
-                let child_sum: usize = cgroup.children.iter().map(|c| c.stat).sum();
+                let child_sum = sum_stats(&cgroup.children);
 
-                if child_sum < cgroup.stat {
+                // Don't add a <self> node if the value is saturated as the difference is unknown
+                if child_sum < cgroup.stat && cgroup.stat != usize::MAX {
                     // Add self quantity
                     let mut sub_rel_path = rel_path.to_path_buf();
                     sub_rel_path.push("<self>");
@@ -159,13 +440,20 @@ fn load_cgroup_rec(
         }
     }
 
+    // Remove children without processes
+    if ctx.options.populated_only {
+        cgroup
+            .children
+            .retain(|c| c.populated || c.path.ends_with("<self>"));
+    }
+
     // Sort the children
-    match sort {
+    match ctx.sort {
         CGroupSortOrder::NameAsc => cgroup.children.sort_by(|a, b| a.path.cmp(&b.path)),
         CGroupSortOrder::NameDsc => cgroup
             .children
             .sort_by(|a, b| a.path.cmp(&b.path).reverse()),
-        CGroupSortOrder::StatAsc => cgroup.children.sort_by(|a, b| a.stat.cmp(&b.stat)),
+        CGroupSortOrder::StatAsc => cgroup.children.sort_by_key(|c| c.stat),
         CGroupSortOrder::StatDsc => cgroup
             .children
             .sort_by(|a, b| a.stat.cmp(&b.stat).reverse()),
@@ -174,19 +462,217 @@ fn load_cgroup_rec(
     Ok(cgroup)
 }
 
-fn cgroup_has_memory_controller(path: &Path) -> io::Result<bool> {
+/// Loads the cgroups in a list of sub directories, keeping the directory order. Sub directories
+/// are loaded on spare worker threads when available, otherwise on the calling thread
+fn load_children(dirs: Vec<(PathBuf, PathBuf)>, ctx: &LoadContext) -> Vec<CGroup> {
+    let load =
+        |abs_path: PathBuf, rel_path: PathBuf| match load_cgroup_rec(abs_path, &rel_path, ctx) {
+            Ok(cgroup) => cgroup,
+            Err(e) => CGroup::new_error(rel_path, e.to_string()),
+        };
+
+    if dirs.len() < 2 {
+        return dirs
+            .into_iter()
+            .map(|(abs_path, rel_path)| load(abs_path, rel_path))
+            .collect();
+    }
+
+    thread::scope(|scope| {
+        // Either the loaded cgroup or the path and worker thread loading it
+        let pending: Vec<_> = dirs
+            .into_iter()
+            .map(|(abs_path, rel_path)| {
+                if ctx.take_thread() {
+                    Err((
+                        rel_path.clone(),
+                        scope.spawn(move || {
+                            let cgroup = load(abs_path, rel_path);
+                            ctx.release_thread();
+                            cgroup
+                        }),
+                    ))
+                } else {
+                    Ok(load(abs_path, rel_path))
+                }
+            })
+            .collect();
+
+        pending
+            .into_iter()
+            .map(|cgroup| {
+                cgroup.unwrap_or_else(|(rel_path, handle)| {
+                    handle.join().unwrap_or_else(|_| {
+                        CGroup::new_error(rel_path, "Worker thread panicked".into())
+                    })
+                })
+            })
+            .collect()
+    })
+}
+
+/// Sums the statistic for a list of cgroups, saturating on overflow
+fn sum_stats(cgroups: &[CGroup]) -> usize {
+    cgroups.iter().fold(0, |sum, c| sum.saturating_add(c.stat))
+}
+
+/// Removes cgroups whose statistic is zero or unreadable unless a descendent has a value
+pub fn remove_empty(cgroups: &mut Vec<CGroup>) {
+    for cgroup in cgroups.iter_mut() {
+        remove_empty(&mut cgroup.children);
+    }
+
+    cgroups.retain(|c| (c.error.is_none() && c.stat > 0) || !c.children.is_empty());
+}
+
+/// Renders a tree of cgroups as text with one "depth value /path" line per node. The value
+/// is the raw statistic value, or "-" if it couldn't be read
+pub fn render_tree_text(cgroups: &[CGroup]) -> String {
+    let mut text = String::new();
+
+    render_tree_text_level(cgroups, 0, &mut text);
+
+    text
+}
+
+fn render_tree_text_level(cgroups: &[CGroup], depth: usize, text: &mut String) {
+    for cg in cgroups {
+        let value = match cg.error {
+            Some(_) => "-".to_string(),
+            None => cg.stat.to_string(),
+        };
+
+        text.push_str(&format!(
+            "{} {} /{}\n",
+            depth,
+            value,
+            cg.path.to_string_lossy()
+        ));
+
+        render_tree_text_level(&cg.children, depth + 1, text);
+    }
+}
+
+/// Loads a file of "key value" lines such as memory.events
+pub fn load_flat_keyed(path: &Path) -> io::Result<Vec<(String, usize)>> {
+    let file = File::open(path)?;
+
+    let mut values = Vec::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+
+        if let Some((key, value)) = line.split_once(' ') {
+            if let Ok(value) = value.trim().parse::<usize>() {
+                values.push((key.to_string(), value));
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// Sums the rbytes and wbytes values for all devices in io.stat
+fn read_io_stat(path: &Path) -> io::Result<(usize, usize)> {
+    let file = File::open(path.join("io.stat"))?;
+
+    let mut rbytes: usize = 0;
+    let mut wbytes: usize = 0;
+
+    // Lines are of the form "<major>:<minor> rbytes=<n> wbytes=<n> ..."
+    for line in BufReader::new(file).lines() {
+        for field in line?.split_whitespace().skip(1) {
+            match field.split_once('=') {
+                Some(("rbytes", value)) => {
+                    rbytes = rbytes.saturating_add(value.parse().unwrap_or(0))
+                }
+                Some(("wbytes", value)) => {
+                    wbytes = wbytes.saturating_add(value.parse().unwrap_or(0))
+                }
+                _ => (),
+            }
+        }
+    }
+
+    Ok((rbytes, wbytes))
+}
+
+/// Reads a space separated list of controllers such as cgroup.controllers
+fn read_controllers(path: &Path, file: &str) -> io::Result<Vec<String>> {
     let mut path = path.to_path_buf();
-    path.push("cgroup.controllers");
+    path.push(file);
 
     let file = File::open(path)?;
 
     match BufReader::new(file).lines().next() {
-        None => Ok(false),
+        None => Ok(Vec::new()),
         Some(Err(e)) => Err(e)?,
-        Some(Ok(line)) => Ok(line.split_whitespace().any(|s| s == "memory")),
+        Some(Ok(line)) => Ok(line.split_whitespace().map(String::from).collect()),
     }
 }
 
+/// Reads a limit file such as memory.max for a cgroup and all of its ancestors. Returns the
+/// configured limit of the cgroup and the effective limit, which is the lowest limit in the
+/// path to the root. "max" is read as usize::MAX
+pub fn effective_limit(
+    cgroup2fs: &Path,
+    cgroup: &Path,
+    file: &str,
+) -> (Option<usize>, Option<usize>) {
+    let processor = get_file_processor(file).unwrap();
+
+    let read = |path: &Path| processor.get_stat(&cgroup2fs.join(path)).ok();
+
+    let configured = read(cgroup);
+
+    // The root cgroup has no limit files
+    let effective = cgroup.ancestors().filter_map(read).min();
+
+    (configured, effective)
+}
+
+/// Reads the populated and frozen flags from cgroup.events
+fn read_event_flags(path: &Path) -> io::Result<CGroupEventFlags> {
+    let values = load_flat_keyed(&path.join("cgroup.events"))?;
+    let flag = |name: &str| values.iter().any(|(key, value)| key == name && *value != 0);
+
+    Ok(CGroupEventFlags {
+        populated: flag("populated"),
+        frozen: flag("frozen"),
+    })
+}
+
+/// Reads the frozen state of a cgroup from cgroup.events
+pub fn cgroup_frozen(cgroup2fs: &Path, cgroup: &Path) -> io::Result<bool> {
+    Ok(read_event_flags(&cgroup2fs.join(cgroup))?.frozen)
+}
+
+/// Freezes or thaws a cgroup and all of its descendents by writing to cgroup.freeze
+pub fn set_cgroup_frozen(cgroup2fs: &Path, cgroup: &Path, frozen: bool) -> io::Result<()> {
+    fs::write(
+        cgroup2fs.join(cgroup).join("cgroup.freeze"),
+        if frozen { "1" } else { "0" },
+    )
+}
+
+/// Gets the cgroup containing this process relative to the cgroup2 mount from the proc
+/// filesystem mounted at proc_root
+pub fn own_cgroup(proc_root: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(proc_root.join("self/cgroup")).ok()?;
+
+    // The cgroup v2 entry is of the form "0::/path"
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(|path| PathBuf::from(path.trim_start_matches('/')))
+}
+
+fn cgroup_has_memory_controller(path: &Path) -> io::Result<bool> {
+    Ok(read_controllers(path, "cgroup.controllers")?
+        .iter()
+        .any(|s| s == "memory"))
+}
+
 /// Gets the path to the mounted cgroup v2 filesystem if available
 pub fn get_cgroup2_mount_point() -> Option<PathBuf> {
     let file_proc = KeyedProcessor::new(3, "cgroup2", 2);
@@ -196,3 +682,167 @@ pub fn get_cgroup2_mount_point() -> Option<PathBuf> {
         Err(_) => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::fixture::Fixture;
+    use super::*;
+
+    /// Loads the cgroup tree of a fixture
+    fn load(fixture: &Fixture, def: &str, stat_type: StatType, self_node: bool) -> CGroup {
+        let ctx = LoadContext::new(
+            get_file_processor(def).unwrap(),
+            stat_type,
+            self_node,
+            CGroupSortOrder::NameAsc,
+            LoadOptions::default(),
+        );
+
+        load_cgroup_rec(fixture.path().to_path_buf(), Path::new(""), &ctx).unwrap()
+    }
+
+    #[test]
+    fn large_tree() {
+        // 50 cgroups with 99 children each, 5000 including the root
+        let mut files = vec![("memory.current".to_string(), "0".to_string())];
+
+        for parent in 0..50 {
+            files.push((format!("p{:02}/memory.current", parent), "0".into()));
+
+            for child in 0..99 {
+                files.push((
+                    format!("p{:02}/c{:02}/memory.current", parent, child),
+                    "1".into(),
+                ));
+            }
+        }
+
+        let files: Vec<(&str, &str)> = files
+            .iter()
+            .map(|(file, contents)| (file.as_str(), contents.as_str()))
+            .collect();
+
+        let fixture = Fixture::new(&files);
+
+        let cgroup = load(&fixture, "memory.current", StatType::Qty, true);
+
+        assert_eq!(cgroup.stat, 50 * 99);
+        assert_eq!(cgroup.children.len(), 50);
+
+        for (i, parent) in cgroup.children.iter().enumerate() {
+            assert_eq!(parent.path, PathBuf::from(format!("p{:02}", i)));
+            assert_eq!(parent.stat, 99);
+            assert_eq!(parent.children.len(), 99);
+            assert!(parent.children.iter().all(|c| c.error.is_none()));
+        }
+    }
+
+    #[test]
+    fn tree_text() {
+        let fixture = Fixture::new(&[
+            ("memory.current", "300"),
+            ("a/memory.current", "100"),
+            ("a/c/memory.current", "100"),
+            ("b/cgroup.procs", ""),
+        ]);
+
+        let cgroup = load(&fixture, "memory.current", StatType::MemQtyCumul, true);
+
+        assert_eq!(
+            render_tree_text(&[cgroup]),
+            "0 300 /\n1 200 /<self>\n1 100 /a\n2 100 /a/c\n1 - /b\n"
+        );
+    }
+
+    #[test]
+    fn event_reads_optional() {
+        let fixture = Fixture::new(&[
+            ("memory.current", "1"),
+            ("memory.events", "low 0\nhigh 3\n"),
+            ("cgroup.events", "populated 1\nfrozen 0\n"),
+        ]);
+
+        let cgroup = load(&fixture, "memory.current", StatType::MemQtyCumul, true);
+        assert_eq!(cgroup.high_events, None);
+        assert!(cgroup.event_flags.is_none());
+
+        let ctx = LoadContext::new(
+            get_file_processor("memory.current").unwrap(),
+            StatType::MemQtyCumul,
+            true,
+            CGroupSortOrder::NameAsc,
+            LoadOptions {
+                high_events: true,
+                event_flags: true,
+                ..Default::default()
+            },
+        );
+
+        let cgroup = load_cgroup_rec(fixture.path().to_path_buf(), Path::new(""), &ctx).unwrap();
+        assert_eq!(cgroup.high_events, Some(3));
+        assert!(cgroup.event_flags.is_some_and(|f| f.populated && !f.frozen));
+    }
+
+    #[test]
+    fn sum_stats_saturates() {
+        let mut a = CGroup::new("a".into());
+        a.stat = usize::MAX;
+        let mut b = CGroup::new("b".into());
+        b.stat = 5;
+
+        assert_eq!(sum_stats(&[a, b]), usize::MAX);
+    }
+
+    #[test]
+    fn quantity_total_saturates() {
+        let fixture = Fixture::new(&[
+            ("memory.max", "10"),
+            ("a/memory.max", "max"),
+            ("b/memory.max", "5"),
+        ]);
+
+        let cgroup = load(&fixture, "memory.max", StatType::Qty, true);
+
+        assert_eq!(cgroup.stat, usize::MAX);
+
+        let stats: Vec<usize> = cgroup.children.iter().map(|c| c.stat).collect();
+        assert_eq!(stats, [10, usize::MAX, 5]);
+    }
+
+    #[test]
+    fn cumulative_self_skipped_when_saturated() {
+        let fixture = Fixture::new(&[
+            ("memory.current", "max"),
+            ("a/memory.current", "max"),
+            ("b/memory.current", "5"),
+        ]);
+
+        let cgroup = load(&fixture, "memory.current", StatType::MemQtyCumul, true);
+
+        assert_eq!(cgroup.stat, usize::MAX);
+        assert_eq!(cgroup.children.len(), 2);
+        assert!(!cgroup.children.iter().any(|c| c.path.ends_with("<self>")));
+    }
+
+    #[test]
+    fn cumulative_self_skipped_when_children_saturate() {
+        let fixture = Fixture::new(&[
+            ("memory.current", "100"),
+            ("a/memory.current", "max"),
+            ("b/memory.current", "5"),
+        ]);
+
+        let cgroup = load(&fixture, "memory.current", StatType::MemQtyCumul, true);
+
+        assert_eq!(cgroup.stat, 100);
+        assert_eq!(cgroup.children.len(), 2);
+    }
+
+    #[test]
+    fn own_cgroup_read_from_proc_root() {
+        let fixture = Fixture::new(&[("self/cgroup", "0::/a/b\n")]);
+
+        assert_eq!(own_cgroup(fixture.path()), Some(PathBuf::from("a/b")));
+        assert_eq!(own_cgroup(&fixture.path().join("missing")), None);
+    }
+}
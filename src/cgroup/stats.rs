@@ -1,4 +1,15 @@
-pub const STATS: [Stat; 17] = [
+use ratatui::text::Span;
+
+use crate::formatters::{
+    format_duration,
+    format_mem_qty,
+    format_pct,
+    format_qty,
+    format_thousands,
+    MemUnits,
+};
+
+pub const STATS: [Stat; 27] = [
     Stat::new(
         "memory.current",
         "Current Total",
@@ -137,14 +148,151 @@ pub const STATS: [Stat; 17] = [
         "",
         ProcStatType::None,
     ),
+    Stat::new(
+        "memory.stat/=/1/slab_reclaimable/2",
+        "Slab Reclaimable",
+        "Part of slab that might be reclaimed, such as dentries and inodes.",
+        StatType::MemQtyCumul,
+        "",
+        "",
+        ProcStatType::None,
+    ),
+    Stat::new(
+        "memory.stat/=/1/slab_unreclaimable/2",
+        "Slab Unreclaimable",
+        "Part of slab that cannot be reclaimed on memory pressure.",
+        StatType::MemQtyCumul,
+        "",
+        "",
+        ProcStatType::None,
+    ),
     Stat::new("cgroup.procs/#", "Processes", "Number of processes.", StatType::Qty, "", "", ProcStatType::None),
     Stat::new("cgroup.threads/#", "Threads", "Number of threads.", StatType::Qty, "", "", ProcStatType::None),
+    Stat::new(
+        "cpu.stat/=/1/usage_usec/2",
+        "CPU Time",
+        "Total CPU time consumed including descendents.",
+        StatType::TimeCumul,
+        "",
+        "",
+        ProcStatType::None,
+    ),
+    // Peaks of descendents don't sum to the peak of the parent so no <self> node is added
+    Stat::new(
+        "memory.peak",
+        "Peak Usage",
+        "Highest total memory usage including descendents recorded.",
+        StatType::MemQtyCumul,
+        "status/=/1/VmHWM:/2",
+        "Peak RSS",
+        ProcStatType::MemQtyKb,
+    )
+    .without_self(),
+    // Limits are set per cgroup so no <self> node is added
+    Stat::new(
+        "memory.max",
+        "Max Limit",
+        "Memory usage hard limit.",
+        StatType::MemQtyCumul,
+        "",
+        "",
+        ProcStatType::None,
+    )
+    .without_self(),
+    Stat::new(
+        "memory.high",
+        "High Limit",
+        "Memory usage throttle limit.",
+        StatType::MemQtyCumul,
+        "",
+        "",
+        ProcStatType::None,
+    )
+    .without_self(),
+    Stat::new(
+        "memory.low",
+        "Low Protection",
+        "Best-effort memory protection.",
+        StatType::MemQtyCumul,
+        "",
+        "",
+        ProcStatType::None,
+    )
+    .without_self(),
+    Stat::new(
+        "memory.min",
+        "Min Protection",
+        "Hard memory protection.",
+        StatType::MemQtyCumul,
+        "",
+        "",
+        ProcStatType::None,
+    )
+    .without_self(),
+    Stat::new(
+        "memory.pressure/%/some/avg10",
+        "Pressure",
+        "Percentage of time some tasks stalled on memory over the last 10 seconds.",
+        StatType::Pct,
+        "",
+        "",
+        ProcStatType::None,
+    ),
+    // There is no cgroup file for virtual size so this is only shown in the process view
+    Stat::new_proc_only(
+        "Virtual Size",
+        "Virtual memory size of processes (process view only).",
+        StatType::MemQty,
+        "status/=/1/VmSize:/2",
+        "VSZ",
+        ProcStatType::MemQtyKb,
+    ),
 ];
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum StatType {
     MemQtyCumul, // Cumulative memory quantity
+    MemQty,      // Memory quantity, non-cumulative
+    TimeCumul,   // Cumulative time in microseconds
     Qty,         // Count, non-cumulative
+    Pct,         // Percentage in hundredths, non-additive
+}
+
+/// How statistic values are formatted for display
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StatFormat {
+    Memory,   // Bytes
+    Duration, // Microseconds
+    Count,    // Plain quantity
+    Percent,  // Hundredths of a percent
+}
+
+impl StatFormat {
+    /// Formats a value abbreviated for display
+    pub fn format(&self, value: usize, units: MemUnits) -> Span<'static> {
+        match self {
+            StatFormat::Memory => format_mem_qty(value, units),
+            StatFormat::Duration => format_duration(value),
+            StatFormat::Count => format_qty(value),
+            StatFormat::Percent => format_pct(value),
+        }
+    }
+
+    /// Formats the exact value with the abbreviated value if different
+    pub fn format_exact(&self, value: usize, units: MemUnits) -> String {
+        let exact = format_thousands(value);
+        let abbrev = self.format(value, units).content.trim().to_string();
+
+        match self {
+            StatFormat::Memory if value == usize::MAX => "unlimited".into(),
+            StatFormat::Memory if units == MemUnits::Bytes => format!("{} bytes", exact),
+            StatFormat::Memory => format!("{} bytes ({})", exact, abbrev),
+            StatFormat::Duration => format!("{} us ({})", exact, abbrev),
+            StatFormat::Count if exact == abbrev => exact,
+            StatFormat::Count => format!("{} ({})", exact, abbrev),
+            StatFormat::Percent => abbrev,
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -153,11 +301,14 @@ pub enum ProcStatType {
     MemQtyKb,
 }
 
+#[derive(Clone, Copy)]
 pub struct Stat<'a> {
-    def: &'a str,
+    def: Option<&'a str>,
     short_desc: &'a str,
     desc: &'a str,
     stype: StatType,
+    format: StatFormat,
+    self_node: bool,
     proc_def: &'a str,
     proc_short_desc: &'a str,
     proc_stype: ProcStatType,
@@ -174,17 +325,53 @@ impl<'a> Stat<'a> {
         proc_stype: ProcStatType,
     ) -> Self {
         Self {
-            def,
+            def: Some(def),
             short_desc,
             desc,
             stype,
+            format: match stype {
+                StatType::MemQtyCumul | StatType::MemQty => StatFormat::Memory,
+                StatType::TimeCumul => StatFormat::Duration,
+                StatType::Qty => StatFormat::Count,
+                StatType::Pct => StatFormat::Percent,
+            },
+            self_node: true,
             proc_def,
             proc_short_desc,
             proc_stype,
         }
     }
 
-    pub fn def(&self) -> &str {
+    /// Creates a statistic without a cgroup file which is only shown in the process view
+    const fn new_proc_only(
+        short_desc: &'a str,
+        desc: &'a str,
+        stype: StatType,
+        proc_def: &'a str,
+        proc_short_desc: &'a str,
+        proc_stype: ProcStatType,
+    ) -> Self {
+        let mut stat = Self::new(
+            "",
+            short_desc,
+            desc,
+            stype,
+            proc_def,
+            proc_short_desc,
+            proc_stype,
+        );
+        stat.def = None;
+        stat
+    }
+
+    /// Disables the <self> node showing the difference between a cgroup and its children
+    const fn without_self(mut self) -> Self {
+        self.self_node = false;
+        self
+    }
+
+    /// Returns the definition of the cgroup file, or None for process only statistics
+    pub fn def(&self) -> Option<&str> {
         self.def
     }
 
@@ -196,10 +383,34 @@ impl<'a> Stat<'a> {
         self.desc
     }
 
+    /// Returns the controller providing the statistic, or None for core cgroup files and
+    /// process only statistics
+    pub fn controller(&self) -> Option<&str> {
+        match self.def?.split(['.', '/']).next() {
+            Some("cgroup") | None => None,
+            controller => controller,
+        }
+    }
+
+    /// Returns true if the statistic has no cgroup file and is only shown for processes
+    pub fn proc_only(&self) -> bool {
+        self.def.is_none()
+    }
+
     pub fn stat_type(&self) -> StatType {
         self.stype
     }
 
+    /// Returns the display format for the statistic
+    pub fn format(&self) -> StatFormat {
+        self.format
+    }
+
+    /// Returns true if a <self> node should be added for inner cgroups
+    pub fn self_node(&self) -> bool {
+        self.self_node
+    }
+
     pub fn proc_def(&self) -> &str {
         self.proc_def
     }
@@ -211,4 +422,79 @@ impl<'a> Stat<'a> {
     pub fn proc_stat_type(&self) -> ProcStatType {
         self.proc_stype
     }
+
+    /// Returns the display format for the process statistic
+    pub fn proc_format(&self) -> StatFormat {
+        match self.proc_stype {
+            ProcStatType::None | ProcStatType::MemQtyKb => StatFormat::Memory,
+        }
+    }
+}
+
+/// Looks up a statistic in a list by definition, short description or 1-based index
+pub fn find_stat(stats: &[Stat], name: &str) -> Option<usize> {
+    if let Ok(index) = name.parse::<usize>() {
+        return (index >= 1 && index <= stats.len()).then(|| index - 1);
+    }
+
+    stats
+        .iter()
+        .position(|s| s.def() == Some(name) || s.short_desc().eq_ignore_ascii_case(name))
+}
+
+/// Returns the first statistic which can be loaded for cgroups
+pub fn first_cgroup_stat(stats: &[Stat]) -> usize {
+    stats.iter().position(|s| !s.proc_only()).unwrap_or(0)
+}
+
+/// Looks up a statistic which can be loaded for cgroups by name, falling back to the first
+/// such statistic if there is no name or it isn't found
+pub fn find_cgroup_stat(stats: &[Stat], name: Option<&str>) -> usize {
+    name.and_then(|name| find_stat(stats, name))
+        .filter(|&i| !stats[i].proc_only())
+        .unwrap_or_else(|| first_cgroup_stat(stats))
+}
+
+/// Builds the runtime statistic list. Statistics listed in the order are placed first,
+/// followed by the remaining statistics in their default order. Process only statistics are
+/// always placed after those which can be loaded for cgroups
+pub fn build_stats(order: &[usize]) -> Vec<Stat<'static>> {
+    let mut indexes: Vec<usize> = Vec::with_capacity(STATS.len());
+
+    for i in order.iter().copied().chain(0..STATS.len()) {
+        if i < STATS.len() && !indexes.contains(&i) {
+            indexes.push(i);
+        }
+    }
+
+    indexes.sort_by_key(|&i| STATS[i].proc_only());
+
+    indexes.into_iter().map(|i| STATS[i]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_format_only_for_memory_types() {
+        for stat in STATS {
+            let memory_type = matches!(stat.stat_type(), StatType::MemQtyCumul | StatType::MemQty);
+
+            assert_eq!(
+                stat.format() == StatFormat::Memory,
+                memory_type,
+                "{}",
+                stat.short_desc()
+            );
+        }
+    }
+
+    #[test]
+    fn cpu_time_is_cumulative_duration() {
+        let stat = STATS.iter().find(|s| s.short_desc() == "CPU Time").unwrap();
+
+        assert!(stat.stat_type() == StatType::TimeCumul);
+        assert!(stat.format() == StatFormat::Duration);
+    }
 }
@@ -0,0 +1,91 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// Clipboard programs tried in order when a display server is available
+const PROGRAMS: [(&str, &str, &[&str]); 3] = [
+    ("WAYLAND_DISPLAY", "wl-copy", &[]),
+    ("DISPLAY", "xclip", &["-selection", "clipboard"]),
+    ("DISPLAY", "xsel", &["--clipboard", "--input"]),
+];
+
+/// Base 64 alphabet for OSC 52 sequences
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Copies text to the clipboard. A clipboard program is used if a display server is available,
+/// otherwise the terminal is asked to set the clipboard with an OSC 52 sequence. Returns the
+/// method used
+pub fn copy_to_clipboard(text: &str) -> io::Result<&'static str> {
+    let mut display = false;
+
+    for (var, program, args) in PROGRAMS {
+        if std::env::var_os(var).is_none_or(|value| value.is_empty()) {
+            continue;
+        }
+
+        display = true;
+
+        // Try the next program if this one isn't installed
+        match run_program(program, args, text) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+            result => return result.map(|_| program),
+        }
+    }
+
+    if display {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No clipboard program found (wl-copy, xclip or xsel)",
+        ));
+    }
+
+    let mut stdout = io::stdout();
+
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()?;
+
+    Ok("terminal")
+}
+
+/// Runs a clipboard program passing the text on stdin
+fn run_program(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{} failed: {}", program, status)))
+    }
+}
+
+/// Encodes bytes as base 64
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - i * 8));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,440 @@
+mod notes;
+mod state;
+mod theme;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+pub use self::notes::Notes;
+pub use self::state::State;
+pub use self::theme::Theme;
+use crate::formatters::{parse_power, DEFAULT_COLOUR_POWERS};
+
+/// Colours which can be configured to start at a power, in order of magnitude
+const COLOUR_NAMES: [&str; 3] = ["blue", "yellow", "red"];
+
+/// Application configuration loaded from the configuration file
+#[derive(Debug, Default)]
+pub struct Config {
+    stat_order: Vec<String>,
+    key_bindings: Vec<(String, char)>,
+    colour_powers: Option<[usize; 3]>,
+}
+
+impl Config {
+    /// Loads the configuration from a file
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+
+        let file = ConfigFile::parse(&contents)?;
+
+        let mut config = Config::default();
+
+        if let Some(value) = file.get("stats", "order") {
+            config.stat_order = value
+                .as_array()
+                .ok_or_else(|| ConfigError::Invalid("stats.order must be an array".into()))?
+                .iter()
+                .map(|v| match v {
+                    ConfigValue::Str(s) => Ok(s.clone()),
+                    ConfigValue::Int(i) => Ok(i.to_string()),
+                    _ => Err(ConfigError::Invalid(
+                        "stats.order entries must be strings or numbers".into(),
+                    )),
+                })
+                .collect::<Result<Vec<String>, ConfigError>>()?;
+        }
+
+        if let Some(keys) = file.section("keys") {
+            for (name, value) in keys {
+                let key = match value {
+                    ConfigValue::Str(s) if s.chars().count() == 1 => s.chars().next().unwrap(),
+                    _ => Err(ConfigError::Invalid(format!(
+                        "keys.{} must be a single character string",
+                        name
+                    )))?,
+                };
+
+                config.key_bindings.push((name.clone(), key));
+            }
+        }
+
+        if let Some(colours) = file.section("colours") {
+            let mut powers = DEFAULT_COLOUR_POWERS;
+
+            for (name, value) in colours {
+                let index = COLOUR_NAMES
+                    .iter()
+                    .position(|n| n == name)
+                    .ok_or_else(|| ConfigError::Invalid(format!("Unknown colour '{}'", name)))?;
+
+                powers[index] = match value {
+                    ConfigValue::Str(s) => parse_power(s),
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    ConfigError::Invalid(format!(
+                        "colours.{} must be one of \"k\", \"M\", \"G\", \"T\", \"P\" or \"E\"",
+                        name
+                    ))
+                })?;
+            }
+
+            if !powers.is_sorted() {
+                Err(ConfigError::Invalid(
+                    "colours must start at increasing powers (blue, yellow, red)".into(),
+                ))?
+            }
+
+            config.colour_powers = Some(powers);
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the preferred statistic ordering
+    pub fn stat_order(&self) -> &Vec<String> {
+        &self.stat_order
+    }
+
+    /// Returns the configured powers at which the blue, yellow and red colours start to apply
+    pub fn colour_powers(&self) -> Option<[usize; 3]> {
+        self.colour_powers
+    }
+
+    /// Returns the configured key bindings as action name and key pairs
+    pub fn key_bindings(&self) -> &Vec<(String, char)> {
+        &self.key_bindings
+    }
+}
+
+/// Gets the configuration directory
+fn config_dir() -> Option<PathBuf> {
+    let mut path = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let mut path = PathBuf::from(std::env::var_os("HOME")?);
+            path.push(".config");
+            path
+        }
+    };
+
+    path.push("cgroup_mem");
+
+    Some(path)
+}
+
+/// Gets the default configuration file path
+pub fn default_config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Gets the default theme file path
+pub fn default_theme_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("theme.toml"))
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    IoError(io::Error),
+    SyntaxError(usize, String),
+    Invalid(String),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::IoError(e) => write!(f, "{}", e),
+            ConfigError::SyntaxError(line, msg) => write!(f, "line {}: {}", line, msg),
+            ConfigError::Invalid(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::IoError(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ConfigValue {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<ConfigValue>),
+}
+
+impl ConfigValue {
+    fn as_array(&self) -> Option<&Vec<ConfigValue>> {
+        match self {
+            ConfigValue::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed configuration file. This is a small subset of TOML consisting of
+/// `[section]` headers and `key = value` lines, where a value is a string,
+/// integer, float, boolean or single line array of those.
+#[derive(Default)]
+struct ConfigFile {
+    sections: HashMap<String, HashMap<String, ConfigValue>>,
+}
+
+impl ConfigFile {
+    fn parse(contents: &str) -> Result<Self, ConfigError> {
+        let mut file = ConfigFile::default();
+        let mut section = String::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line_no = i + 1;
+            let line = strip_comment(line).trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                // Section header
+                match name.strip_suffix(']') {
+                    Some(name) if !name.trim().is_empty() => section = name.trim().to_string(),
+                    _ => Err(ConfigError::SyntaxError(
+                        line_no,
+                        "Invalid section header".into(),
+                    ))?,
+                }
+
+                continue;
+            }
+
+            // Key / value pair
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ConfigError::SyntaxError(line_no, "Expected key = value".into()))?;
+
+            let key = key.trim().trim_matches('"');
+
+            if key.is_empty() {
+                Err(ConfigError::SyntaxError(line_no, "Missing key".into()))?
+            }
+
+            let value = parse_value(value.trim())
+                .ok_or_else(|| ConfigError::SyntaxError(line_no, "Invalid value".into()))?;
+
+            file.sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.to_string(), value);
+        }
+
+        Ok(file)
+    }
+
+    fn get(&self, section: &str, key: &str) -> Option<&ConfigValue> {
+        self.sections.get(section).and_then(|s| s.get(key))
+    }
+
+    fn section(&self, section: &str) -> Option<&HashMap<String, ConfigValue>> {
+        self.sections.get(section)
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => (),
+        }
+    }
+
+    line
+}
+
+fn parse_value(value: &str) -> Option<ConfigValue> {
+    if let Some(inner) = value.strip_prefix('[') {
+        // Array
+        let inner = inner.strip_suffix(']')?.trim();
+
+        if inner.is_empty() {
+            return Some(ConfigValue::Array(Vec::new()));
+        }
+
+        split_array(inner)?
+            .into_iter()
+            .map(|v| parse_value(v.trim()))
+            .collect::<Option<Vec<ConfigValue>>>()
+            .map(ConfigValue::Array)
+    } else if let Some(inner) = value.strip_prefix('"') {
+        // String
+        let inner = inner.strip_suffix('"')?;
+
+        if inner.contains('"') {
+            return None;
+        }
+
+        Some(ConfigValue::Str(inner.replace("\\\\", "\\")))
+    } else if value == "true" {
+        Some(ConfigValue::Bool(true))
+    } else if value == "false" {
+        Some(ConfigValue::Bool(false))
+    } else if let Ok(i) = value.parse::<i64>() {
+        Some(ConfigValue::Int(i))
+    } else if let Ok(f) = value.parse::<f64>() {
+        Some(ConfigValue::Float(f))
+    } else {
+        None
+    }
+}
+
+fn split_array(inner: &str) -> Option<Vec<&str>> {
+    let mut items = Vec::new();
+    let mut in_string = false;
+    let mut start = 0;
+
+    for (i, c) in inner.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                items.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    if in_string {
+        return None;
+    }
+
+    // Allow a trailing comma
+    let last = &inner[start..];
+
+    if !last.trim().is_empty() {
+        items.push(last);
+    }
+
+    Some(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cgroup::fixture::Fixture;
+
+    /// Returns the line number of a syntax error from parsing the contents
+    fn syntax_error_line(contents: &str) -> Option<usize> {
+        match ConfigFile::parse(contents) {
+            Err(ConfigError::SyntaxError(line, _)) => Some(line),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn values_parsed() {
+        let file = ConfigFile::parse(concat!(
+            "# Comment\n",
+            "top = 1\n",
+            "\n",
+            "[ section ]\n",
+            "\"quoted\" = \"a # not a comment\" # comment\n",
+            "path = \"C:\\\\dir\"\n",
+            "int = -5\n",
+            "float = 1.5\n",
+            "bool = false\n",
+            "array = [\"a,b\", 2, true, ]\n",
+            "empty = []\n",
+        ))
+        .unwrap();
+
+        assert_eq!(file.get("", "top"), Some(&ConfigValue::Int(1)));
+
+        let value = |key| file.get("section", key).cloned();
+
+        assert_eq!(
+            value("quoted"),
+            Some(ConfigValue::Str("a # not a comment".into()))
+        );
+        assert_eq!(value("path"), Some(ConfigValue::Str("C:\\dir".into())));
+        assert_eq!(value("int"), Some(ConfigValue::Int(-5)));
+        assert_eq!(value("float"), Some(ConfigValue::Float(1.5)));
+        assert_eq!(value("bool"), Some(ConfigValue::Bool(false)));
+        assert_eq!(
+            value("array"),
+            Some(ConfigValue::Array(vec![
+                ConfigValue::Str("a,b".into()),
+                ConfigValue::Int(2),
+                ConfigValue::Bool(true),
+            ]))
+        );
+        assert_eq!(value("empty"), Some(ConfigValue::Array(Vec::new())));
+        assert_eq!(value("missing"), None);
+    }
+
+    #[test]
+    fn syntax_errors_report_line() {
+        assert_eq!(syntax_error_line("[ok]\n[bad\n"), Some(2));
+        assert_eq!(syntax_error_line("[]\n"), Some(1));
+        assert_eq!(syntax_error_line("a = 1\n\nno value\n"), Some(3));
+        assert_eq!(syntax_error_line("= 1\n"), Some(1));
+        assert_eq!(syntax_error_line("a = \"unterminated\n"), Some(1));
+        assert_eq!(syntax_error_line("a = \"x\"y\"\n"), Some(1));
+        assert_eq!(syntax_error_line("a = [\"x, 1]\n"), Some(1));
+        assert_eq!(syntax_error_line("a = word\n"), Some(1));
+        assert_eq!(syntax_error_line("# a = word\n"), None);
+    }
+
+    #[test]
+    fn config_loaded() {
+        let fixture = Fixture::new(&[(
+            "config.toml",
+            concat!(
+                "[stats]\n",
+                "order = [\"memory.current\", 3]\n",
+                "unknown = 1\n",
+                "[keys]\n",
+                "quit = \"x\"\n",
+                "[colours]\n",
+                "red = \"T\"\n",
+                "[unknown]\n",
+                "key = \"value\"\n",
+            ),
+        )]);
+
+        let config = Config::load(&fixture.path().join("config.toml")).unwrap();
+
+        assert_eq!(config.stat_order(), &["memory.current", "3"]);
+        assert_eq!(config.key_bindings(), &[("quit".to_string(), 'x')]);
+
+        let mut powers = DEFAULT_COLOUR_POWERS;
+        powers[2] = parse_power("T").unwrap();
+        assert_eq!(config.colour_powers(), Some(powers));
+    }
+
+    #[test]
+    fn invalid_config_rejected() {
+        for contents in [
+            "[stats]\norder = \"memory.current\"\n",
+            "[keys]\nquit = \"xy\"\n",
+            "[colours]\npurple = \"k\"\n",
+            "[colours]\nblue = \"G\"\nyellow = \"k\"\n",
+        ] {
+            let fixture = Fixture::new(&[("config.toml", contents)]);
+
+            assert!(
+                matches!(
+                    Config::load(&fixture.path().join("config.toml")),
+                    Err(ConfigError::Invalid(_))
+                ),
+                "{:?} accepted",
+                contents
+            );
+        }
+    }
+}
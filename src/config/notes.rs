@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::config_dir;
+
+/// Notes attached to cgroups by path, persisted in the configuration directory.
+/// Notes for cgroups which no longer exist are kept in case the cgroup is recreated
+#[derive(Debug, Default)]
+pub struct Notes {
+    file: Option<PathBuf>,
+    notes: HashMap<PathBuf, String>,
+}
+
+impl Notes {
+    /// Loads the notes from the default notes file. Returns no notes if the file doesn't exist
+    pub fn load_default() -> Self {
+        let file = config_dir().map(|dir| dir.join("notes"));
+
+        let notes = file
+            .as_ref()
+            .and_then(|file| fs::read_to_string(file).ok())
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default();
+
+        Self { file, notes }
+    }
+
+    /// Parses lines of the form "/cgroup/path<tab>note"
+    fn parse(contents: &str) -> HashMap<PathBuf, String> {
+        contents
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(path, note)| {
+                (
+                    PathBuf::from(path.trim_start_matches('/')),
+                    note.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the note for a cgroup
+    pub fn get(&self, path: &Path) -> Option<&str> {
+        self.notes.get(path).map(|note| note.as_str())
+    }
+
+    /// Sets or removes (if empty) the note for a cgroup and saves the notes file
+    pub fn set(&mut self, path: PathBuf, note: &str) -> io::Result<()> {
+        let note = note.trim();
+
+        if note.is_empty() {
+            self.notes.remove(&path);
+        } else {
+            self.notes.insert(path, note.replace('\t', " "));
+        }
+
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let file = self
+            .file
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No configuration directory"))?;
+
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut paths: Vec<&PathBuf> = self.notes.keys().collect();
+        paths.sort();
+
+        let mut out = fs::File::create(file)?;
+
+        writeln!(out, "# cgroup_mem notes")?;
+
+        for path in paths {
+            writeln!(out, "/{}\t{}", path.to_string_lossy(), self.notes[path])?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::cgroup::CGroupSortOrder;
+use crate::proc::ProcSortOrder;
+
+/// Display state persisted between runs
+#[derive(Debug, Default, Clone)]
+pub struct State {
+    /// Short description of the statistic
+    pub stat: Option<String>,
+    pub cgroup_sort: Option<CGroupSortOrder>,
+    pub proc_sort: Option<ProcSortOrder>,
+}
+
+impl State {
+    /// Loads the state from the state file. Values missing or invalid in the file are left unset
+    pub fn load() -> Self {
+        state_file()
+            .and_then(|file| fs::read_to_string(file).ok())
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    /// Parses lines of the form "key = value"
+    fn parse(contents: &str) -> Self {
+        let mut state = Self::default();
+
+        for (key, value) in contents
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+        {
+            match key {
+                "stat" => state.stat = Some(value.to_string()).filter(|s| !s.is_empty()),
+                "cgroup-sort" => state.cgroup_sort = CGroupSortOrder::from_name(value),
+                "proc-sort" => state.proc_sort = ProcSortOrder::from_name(value),
+                _ => (),
+            }
+        }
+
+        state
+    }
+
+    /// Saves the state to the state file
+    pub fn save(&self) -> io::Result<()> {
+        let file = state_file()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No state directory"))?;
+
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut out = fs::File::create(file)?;
+
+        writeln!(out, "# cgroup_mem state")?;
+
+        if let Some(stat) = &self.stat {
+            writeln!(out, "stat = {}", stat)?;
+        }
+
+        if let Some(sort) = self.cgroup_sort {
+            writeln!(out, "cgroup-sort = {}", sort.name())?;
+        }
+
+        if let Some(sort) = self.proc_sort {
+            writeln!(out, "proc-sort = {}", sort.name())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Gets the state file path, $XDG_STATE_HOME/cgroup_mem/state or ~/.local/state/cgroup_mem/state
+fn state_file() -> Option<PathBuf> {
+    let mut path = match std::env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let mut path = PathBuf::from(std::env::var_os("HOME")?);
+            path.push(".local");
+            path.push("state");
+            path
+        }
+    };
+
+    path.push("cgroup_mem");
+    path.push("state");
+
+    Some(path)
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use ratatui::style::{Color, Modifier, Style};
+
+use super::{ConfigError, ConfigFile, ConfigValue};
+use crate::formatters::COLOURS;
+
+/// Colours and styles used to draw the user interface
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Colours indicating the magnitude of a value, from smallest to largest
+    pub colours: [Color; 4],
+    /// Style of table headers and footers
+    pub header: Style,
+    /// Style of the selected row
+    pub highlight: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            colours: COLOURS,
+            header: Style::default().bg(Color::Blue),
+            highlight: Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+impl Theme {
+    /// Loads a theme from a file. Settings missing from the file keep their defaults
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+
+        let file = ConfigFile::parse(&contents)?;
+
+        let mut theme = Theme::default();
+
+        if let Some(value) = file.get("magnitude", "colours") {
+            let colours = value
+                .as_array()
+                .filter(|a| a.len() == theme.colours.len())
+                .ok_or_else(|| {
+                    ConfigError::Invalid("magnitude.colours must be an array of 4 colours".into())
+                })?;
+
+            for (colour, value) in theme.colours.iter_mut().zip(colours) {
+                *colour = parse_colour("magnitude.colours", value)?;
+            }
+        }
+
+        if let Some(section) = file.section("header") {
+            theme.header = parse_style("header", section, theme.header)?;
+        }
+
+        if let Some(section) = file.section("highlight") {
+            theme.highlight = parse_style("highlight", section, theme.highlight)?;
+        }
+
+        Ok(theme)
+    }
+}
+
+/// Parses a colour name, number from 0 to 255 or #rrggbb value
+fn parse_colour(name: &str, value: &ConfigValue) -> Result<Color, ConfigError> {
+    match value {
+        ConfigValue::Str(s) => s.parse().ok(),
+        ConfigValue::Int(i) => u8::try_from(*i).ok().map(Color::Indexed),
+        _ => None,
+    }
+    .ok_or_else(|| ConfigError::Invalid(format!("{} has an invalid colour", name)))
+}
+
+/// Applies fg, bg, bold and reversed settings from a section to a style
+fn parse_style(
+    section_name: &str,
+    section: &HashMap<String, ConfigValue>,
+    mut style: Style,
+) -> Result<Style, ConfigError> {
+    for (key, value) in section {
+        let name = format!("{}.{}", section_name, key);
+
+        match key.as_str() {
+            "fg" => style = style.fg(parse_colour(&name, value)?),
+            "bg" => style = style.bg(parse_colour(&name, value)?),
+            "bold" | "reversed" => {
+                let modifier = if key == "bold" {
+                    Modifier::BOLD
+                } else {
+                    Modifier::REVERSED
+                };
+
+                style = match value {
+                    ConfigValue::Bool(true) => style.add_modifier(modifier),
+                    ConfigValue::Bool(false) => style.remove_modifier(modifier),
+                    _ => Err(ConfigError::Invalid(format!(
+                        "{} must be true or false",
+                        name
+                    )))?,
+                };
+            }
+            _ => Err(ConfigError::Invalid(format!("Unknown setting '{}'", name)))?,
+        }
+    }
+
+    Ok(style)
+}
@@ -1,8 +1,7 @@
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::path::Path;
 
-use super::{FileProcessor, FileProcessorError};
+use super::{open_file, FileProcessor, FileProcessorError};
 
 #[derive(Default)]
 pub struct CountProcessor {
@@ -17,20 +16,41 @@ impl CountProcessor {
     pub fn set_file(&mut self, file: &str) {
         self.file = Some(file.to_string())
     }
+
+    /// Returns the number of lines read
+    pub fn get_value_from(&self, reader: impl BufRead) -> Result<String, FileProcessorError> {
+        Ok(reader.lines().count().to_string())
+    }
 }
 
 impl FileProcessor for CountProcessor {
     fn get_value(&self, path: &Path) -> Result<String, FileProcessorError> {
-        let mut path = path.to_path_buf();
-
-        if let Some(file) = &self.file {
-            path.push(file);
-        }
+        self.get_value_from(open_file(path, self.file.as_deref())?)
+    }
+}
 
-        let file = File::open(path)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_lines() {
+        let proc = CountProcessor::new();
+
+        assert_eq!(
+            proc.get_value_from("1\n22\n333\n".as_bytes()).ok(),
+            Some("3".into())
+        );
+        assert_eq!(
+            proc.get_value_from("1\n22".as_bytes()).ok(),
+            Some("2".into())
+        );
+    }
 
-        let buf_reader = io::BufReader::new(file);
+    #[test]
+    fn empty_file() {
+        let proc = CountProcessor::new();
 
-        Ok(buf_reader.lines().count().to_string())
+        assert_eq!(proc.get_value_from("".as_bytes()).ok(), Some("0".into()));
     }
 }
@@ -1,14 +1,20 @@
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::path::Path;
 
-use super::{FileProcessor, FileProcessorError};
+use regex::Regex;
+
+use super::{open_file, FileProcessor, FileProcessorError};
+
+/// How the match column is compared
+enum KeyMatch {
+    Exact(String),
+    Pattern(Regex),
+}
 
-#[derive(Default)]
 pub struct KeyedProcessor {
     file: Option<String>,
     match_col: usize,
-    match_val: String,
+    key: KeyMatch,
     ret_col: usize,
 }
 
@@ -17,34 +23,42 @@ impl KeyedProcessor {
         Self {
             file: None,
             match_col,
-            match_val: match_val.into(),
+            key: KeyMatch::Exact(match_val.into()),
             ret_col,
         }
     }
 
-    pub fn set_file(&mut self, file: &str) {
-        self.file = Some(file.to_string())
+    /// Creates a processor matching the column against a regular expression. Returns None if
+    /// the pattern is invalid. The pattern can't contain a / as it separates the fields of the
+    /// definition
+    pub fn new_pattern(match_col: usize, pattern: &str, ret_col: usize) -> Option<Self> {
+        Some(Self {
+            file: None,
+            match_col,
+            key: KeyMatch::Pattern(Regex::new(pattern).ok()?),
+            ret_col,
+        })
     }
-}
-
-impl FileProcessor for KeyedProcessor {
-    fn get_value(&self, path: &Path) -> Result<String, FileProcessorError> {
-        let mut path = path.to_path_buf();
 
-        if let Some(file) = &self.file {
-            path.push(file);
+    fn key_matches(&self, column: &str) -> bool {
+        match &self.key {
+            KeyMatch::Exact(value) => column == value,
+            KeyMatch::Pattern(pattern) => pattern.is_match(column),
         }
+    }
 
-        let file = File::open(path)?;
-
-        let buf_reader = io::BufReader::new(file);
+    pub fn set_file(&mut self, file: &str) {
+        self.file = Some(file.to_string())
+    }
 
-        for line in buf_reader.lines() {
+    /// Returns the value from the first line matching the key
+    pub fn get_value_from(&self, reader: impl BufRead) -> Result<String, FileProcessorError> {
+        for line in reader.lines() {
             let line = line?;
 
             let columns: Vec<&str> = line.split_whitespace().collect();
 
-            if self.match_col < columns.len() && columns[self.match_col - 1] == self.match_val {
+            if self.match_col < columns.len() && self.key_matches(columns[self.match_col - 1]) {
                 if self.ret_col > columns.len() {
                     return Err(FileProcessorError::ValueNotFound);
                 } else {
@@ -56,3 +70,104 @@ impl FileProcessor for KeyedProcessor {
         Err(FileProcessorError::ValueNotFound)
     }
 }
+
+impl FileProcessor for KeyedProcessor {
+    fn get_value(&self, path: &Path) -> Result<String, FileProcessorError> {
+        self.get_value_from(open_file(path, self.file.as_deref())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENTS: &str = "low 0\nhigh 12\nmax max\noom\n";
+
+    #[test]
+    fn exact_key() {
+        let proc = KeyedProcessor::new(1, "high", 2);
+
+        assert_eq!(
+            proc.get_value_from(EVENTS.as_bytes()).ok(),
+            Some("12".into())
+        );
+    }
+
+    #[test]
+    fn max_value() {
+        let proc = KeyedProcessor::new(1, "max", 2);
+
+        assert_eq!(
+            proc.get_value_from(EVENTS.as_bytes()).ok(),
+            Some("max".into())
+        );
+    }
+
+    #[test]
+    fn missing_key() {
+        let proc = KeyedProcessor::new(1, "oom_kill", 2);
+
+        assert!(matches!(
+            proc.get_value_from(EVENTS.as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
+    }
+
+    #[test]
+    fn missing_value() {
+        let proc = KeyedProcessor::new(1, "high", 3);
+
+        assert!(matches!(
+            proc.get_value_from(EVENTS.as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
+    }
+
+    #[test]
+    fn malformed_lines_skipped() {
+        let proc = KeyedProcessor::new(1, "high", 2);
+
+        assert_eq!(
+            proc.get_value_from("\nhigh\n   \nhigh 7\n".as_bytes()).ok(),
+            Some("7".into())
+        );
+    }
+
+    #[test]
+    fn pattern_key() {
+        let proc = KeyedProcessor::new_pattern(1, "^VmH.*:$", 2).unwrap();
+
+        assert_eq!(
+            proc.get_value_from("VmPeak: 100 kB\nVmHWM: 50 kB\n".as_bytes())
+                .ok(),
+            Some("50".into())
+        );
+    }
+
+    #[test]
+    fn pattern_unanchored() {
+        let proc = KeyedProcessor::new_pattern(1, r"slab_\w+", 2).unwrap();
+
+        assert_eq!(
+            proc.get_value_from("slab 30\nnr_slab_reclaimable 10\n".as_bytes())
+                .ok(),
+            Some("10".into())
+        );
+    }
+
+    #[test]
+    fn pattern_not_matched() {
+        let proc = KeyedProcessor::new_pattern(1, "^anon$", 2).unwrap();
+
+        assert!(matches!(
+            proc.get_value_from("active_anon 5\ninactive_anon 6\n".as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(KeyedProcessor::new_pattern(1, "[a-z", 2).is_none());
+        assert!(KeyedProcessor::new_pattern(1, "*a", 2).is_none());
+    }
+}
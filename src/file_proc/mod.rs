@@ -1,23 +1,34 @@
 mod count;
 mod keyed;
+mod pressure;
 mod single_value;
+mod sum;
 
 use std::fmt::Display;
-use std::io;
+use std::fs::File;
+use std::io::{self, BufReader};
 use std::num::ParseIntError;
 use std::path::Path;
 
 pub use self::count::CountProcessor;
 pub use self::keyed::KeyedProcessor;
+pub use self::pressure::PressureProcessor;
 pub use self::single_value::SingleValueProcessor;
+pub use self::sum::SumProcessor;
 
-pub trait FileProcessor {
+pub trait FileProcessor: Send + Sync {
     fn get_value(&self, path: &Path) -> Result<String, FileProcessorError>;
 }
 
 impl dyn FileProcessor + '_ {
     pub fn get_stat(&self, path: &Path) -> Result<usize, FileProcessorError> {
         let value = self.get_value(path)?;
+
+        // Limits contain "max" when unlimited
+        if value == "max" {
+            return Ok(usize::MAX);
+        }
+
         Ok(value.parse::<usize>()?)
     }
 }
@@ -50,6 +61,17 @@ impl From<ParseIntError> for FileProcessorError {
     }
 }
 
+/// Opens a file, optionally within a directory, for reading
+fn open_file(path: &Path, file: Option<&str>) -> io::Result<BufReader<File>> {
+    let mut path = path.to_path_buf();
+
+    if let Some(file) = file {
+        path.push(file);
+    }
+
+    Ok(BufReader::new(File::open(path)?))
+}
+
 pub fn get_file_processor(def: &str) -> Option<Box<dyn FileProcessor>> {
     let split: Vec<&str> = def.split('/').collect();
 
@@ -66,8 +88,10 @@ pub fn get_file_processor(def: &str) -> Option<Box<dyn FileProcessor>> {
     }
 
     match split[1] {
-        "=" => {
-            // Format is "filename/=/<matchcol>/<string>/<retcol>" for keyed processor
+        "=" | "~" => {
+            // Format is "filename/=/<matchcol>/<string>/<retcol>" for keyed processor, or
+            // "filename/~/<matchcol>/<regex>/<retcol>" to match a regular expression, which
+            // can't contain a /
             // Columns are counted from 1
             if split.len() != 5 || split[3].is_empty() {
                 return None;
@@ -87,7 +111,22 @@ pub fn get_file_processor(def: &str) -> Option<Box<dyn FileProcessor>> {
                 return None;
             }
 
-            let mut proc = KeyedProcessor::new(match_col, split[3], ret_col);
+            let mut proc = if split[1] == "~" {
+                KeyedProcessor::new_pattern(match_col, split[3], ret_col)?
+            } else {
+                KeyedProcessor::new(match_col, split[3], ret_col)
+            };
+
+            proc.set_file(split[0]);
+            Some(Box::new(proc))
+        }
+        "%" => {
+            // Format is "filename/%/<line>/<field>" for pressure processor
+            if split.len() != 4 || split[2].is_empty() || split[3].is_empty() {
+                return None;
+            }
+
+            let mut proc = PressureProcessor::new(split[2], split[3]);
             proc.set_file(split[0]);
             Some(Box::new(proc))
         }
@@ -101,6 +140,30 @@ pub fn get_file_processor(def: &str) -> Option<Box<dyn FileProcessor>> {
             proc.set_file(split[0]);
             Some(Box::new(proc))
         }
+        "+" => {
+            // Format is "filename/+/<key>/<key>..." for sum processor
+            if split.len() < 3 || split[2..].iter().any(|key| key.is_empty()) {
+                return None;
+            }
+
+            let mut proc = SumProcessor::new(&split[2..]);
+            proc.set_file(split[0]);
+            Some(Box::new(proc))
+        }
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern_definitions() {
+        assert!(get_file_processor("memory.stat/~/1/^slab_.*$/2").is_some());
+        assert!(get_file_processor("memory.stat/~/1/[a-z/2").is_none());
+
+        // A / in the pattern splits it in to extra fields
+        assert!(get_file_processor("memory.stat/~/1/a/b/2").is_none());
+    }
+}
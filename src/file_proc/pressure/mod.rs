@@ -0,0 +1,107 @@
+use std::io::BufRead;
+use std::path::Path;
+
+use super::{open_file, FileProcessor, FileProcessorError};
+
+/// Extracts a percentage from a pressure stall information file, returned in hundredths
+#[derive(Default)]
+pub struct PressureProcessor {
+    file: Option<String>,
+    line: String,
+    field: String,
+}
+
+impl PressureProcessor {
+    pub fn new(line: &str, field: &str) -> Self {
+        Self {
+            file: None,
+            line: line.into(),
+            field: field.into(),
+        }
+    }
+
+    pub fn set_file(&mut self, file: &str) {
+        self.file = Some(file.to_string())
+    }
+
+    /// Returns the field from the matching line in hundredths
+    pub fn get_value_from(&self, reader: impl BufRead) -> Result<String, FileProcessorError> {
+        for line in reader.lines() {
+            let line = line?;
+
+            let mut columns = line.split_whitespace();
+
+            if columns.next() == Some(&self.line) {
+                // Look for "<field>=<value>"
+                for column in columns {
+                    if let Some((key, value)) = column.split_once('=') {
+                        if key == self.field {
+                            return match value.parse::<f64>() {
+                                Ok(pct) => Ok(((pct * 100_f64).round() as usize).to_string()),
+                                Err(_) => Err(FileProcessorError::ValueNotFound),
+                            };
+                        }
+                    }
+                }
+
+                break;
+            }
+        }
+
+        Err(FileProcessorError::ValueNotFound)
+    }
+}
+
+impl FileProcessor for PressureProcessor {
+    fn get_value(&self, path: &Path) -> Result<String, FileProcessorError> {
+        self.get_value_from(open_file(path, self.file.as_deref())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRESSURE: &str = "some avg10=1.23 avg60=0.50 avg300=0.00 total=1234\n\
+                            full avg10=0.05 avg60=0.00 avg300=0.00 total=56\n";
+
+    #[test]
+    fn field_in_hundredths() {
+        let proc = PressureProcessor::new("some", "avg10");
+        assert_eq!(
+            proc.get_value_from(PRESSURE.as_bytes()).ok(),
+            Some("123".into())
+        );
+
+        let proc = PressureProcessor::new("full", "avg10");
+        assert_eq!(
+            proc.get_value_from(PRESSURE.as_bytes()).ok(),
+            Some("5".into())
+        );
+    }
+
+    #[test]
+    fn missing_line_or_field() {
+        let proc = PressureProcessor::new("none", "avg10");
+        assert!(matches!(
+            proc.get_value_from(PRESSURE.as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
+
+        let proc = PressureProcessor::new("some", "avg5");
+        assert!(matches!(
+            proc.get_value_from(PRESSURE.as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
+    }
+
+    #[test]
+    fn malformed_value() {
+        let proc = PressureProcessor::new("some", "avg10");
+
+        assert!(matches!(
+            proc.get_value_from("some avg10=high avg60\n".as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
+    }
+}
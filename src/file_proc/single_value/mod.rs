@@ -1,8 +1,7 @@
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::path::Path;
 
-use super::{FileProcessor, FileProcessorError};
+use super::{open_file, FileProcessor, FileProcessorError};
 
 #[derive(Default)]
 pub struct SingleValueProcessor {
@@ -17,22 +16,48 @@ impl SingleValueProcessor {
     pub fn set_file(&mut self, file: &str) {
         self.file = Some(file.to_string())
     }
+
+    /// Returns the first line read
+    pub fn get_value_from(&self, reader: impl BufRead) -> Result<String, FileProcessorError> {
+        match reader.lines().next() {
+            None => Err(FileProcessorError::ValueNotFound)?,
+            Some(Err(e)) => Err(e)?,
+            Some(Ok(line)) => Ok(line),
+        }
+    }
 }
 
 impl FileProcessor for SingleValueProcessor {
     fn get_value(&self, path: &Path) -> Result<String, FileProcessorError> {
-        let mut path = path.to_path_buf();
+        self.get_value_from(open_file(path, self.file.as_deref())?)
+    }
+}
 
-        if let Some(file) = &self.file {
-            path.push(file);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_line() {
+        let proc = SingleValueProcessor::new();
+
+        assert_eq!(
+            proc.get_value_from("1234\n5678\n".as_bytes()).ok(),
+            Some("1234".into())
+        );
+        assert_eq!(
+            proc.get_value_from("max\n".as_bytes()).ok(),
+            Some("max".into())
+        );
+    }
 
-        let file = File::open(path)?;
+    #[test]
+    fn empty_file() {
+        let proc = SingleValueProcessor::new();
 
-        match io::BufReader::new(file).lines().next() {
-            None => Err(FileProcessorError::ValueNotFound)?,
-            Some(Err(e)) => Err(e)?,
-            Some(Ok(line)) => Ok(line),
-        }
+        assert!(matches!(
+            proc.get_value_from("".as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
     }
 }
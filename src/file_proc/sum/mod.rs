@@ -0,0 +1,131 @@
+use std::io::BufRead;
+use std::path::Path;
+
+use super::{open_file, FileProcessor, FileProcessorError};
+
+#[derive(Default)]
+pub struct SumProcessor {
+    file: Option<String>,
+    keys: Vec<String>,
+}
+
+impl SumProcessor {
+    pub fn new(keys: &[&str]) -> Self {
+        Self {
+            file: None,
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    pub fn set_file(&mut self, file: &str) {
+        self.file = Some(file.to_string())
+    }
+
+    /// Returns the sum of the values of the keys found
+    pub fn get_value_from(&self, reader: impl BufRead) -> Result<String, FileProcessorError> {
+        // Missing keys count as zero as long as at least one is found
+        let mut sum: usize = 0;
+        let mut found = false;
+
+        for line in reader.lines() {
+            let line = line?;
+
+            let mut columns = line.split_whitespace();
+
+            if let (Some(key), Some(value)) = (columns.next(), columns.next()) {
+                if self.keys.iter().any(|k| k == key) {
+                    sum = sum.saturating_add(value.parse::<usize>()?);
+                    found = true;
+                }
+            }
+        }
+
+        if found {
+            Ok(sum.to_string())
+        } else {
+            Err(FileProcessorError::ValueNotFound)
+        }
+    }
+}
+
+impl FileProcessor for SumProcessor {
+    fn get_value(&self, path: &Path) -> Result<String, FileProcessorError> {
+        self.get_value_from(open_file(path, self.file.as_deref())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_keys() {
+        let proc = SumProcessor::new(&["anon", "file"]);
+
+        assert_eq!(
+            proc.get_value_from("anon 10\nkernel 5\nfile 20\n".as_bytes())
+                .ok(),
+            Some("30".into())
+        );
+    }
+
+    #[test]
+    fn missing_keys_count_as_zero() {
+        let proc = SumProcessor::new(&["anon", "file", "shmem"]);
+
+        assert_eq!(
+            proc.get_value_from("anon 10\nkernel 5\n".as_bytes()).ok(),
+            Some("10".into())
+        );
+        assert_eq!(
+            proc.get_value_from("file 0\n".as_bytes()).ok(),
+            Some("0".into())
+        );
+    }
+
+    #[test]
+    fn sum_saturates() {
+        let proc = SumProcessor::new(&["anon", "file"]);
+
+        assert_eq!(
+            proc.get_value_from(format!("anon {}\nfile 1\n", usize::MAX).as_bytes())
+                .ok(),
+            Some(usize::MAX.to_string())
+        );
+    }
+
+    #[test]
+    fn no_keys_found() {
+        let proc = SumProcessor::new(&["anon"]);
+
+        assert!(matches!(
+            proc.get_value_from("kernel 5\n".as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
+
+        assert!(matches!(
+            proc.get_value_from("".as_bytes()),
+            Err(FileProcessorError::ValueNotFound)
+        ));
+    }
+
+    #[test]
+    fn malformed_lines_skipped() {
+        let proc = SumProcessor::new(&["anon"]);
+
+        assert_eq!(
+            proc.get_value_from("anon\n\nanon 3\n".as_bytes()).ok(),
+            Some("3".into())
+        );
+    }
+
+    #[test]
+    fn invalid_value() {
+        let proc = SumProcessor::new(&["anon"]);
+
+        assert!(matches!(
+            proc.get_value_from("anon max\n".as_bytes()),
+            Err(FileProcessorError::ParseError(_))
+        ));
+    }
+}
@@ -1,29 +1,126 @@
 use std::iter::successors;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use ratatui::style::{Color, Style};
 use ratatui::text::Span;
 
-const POWERS: [&str; 7] = [" ", "k", "M", "G", "T", "P", "E"];
-const COLOURS: [Color; 7] = [
+use crate::config::Theme;
+
+/// Suffixes for each power of 1000
+pub const POWERS: [&str; 7] = [" ", "k", "M", "G", "T", "P", "E"];
+const BINARY_UNITS: [&str; 7] = ["B  ", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+const DECIMAL_UNITS: [&str; 7] = ["B ", "kB", "MB", "GB", "TB", "PB", "EB"];
+
+/// Default colours indicating the magnitude of a value, from smallest to largest
+pub const COLOURS: [Color; 4] = [
     Color::LightGreen,
     Color::LightBlue,
     Color::LightYellow,
     Color::LightRed,
-    Color::LightRed,
-    Color::LightRed,
-    Color::LightRed,
 ];
 
-pub fn format_mem_qty(bytes: usize) -> Span<'static> {
+/// Default powers at which the blue, yellow and red colours start to apply
+pub const DEFAULT_COLOUR_POWERS: [usize; 3] = [1, 2, 3];
+
+/// Configured powers at which the blue, yellow and red colours start to apply
+static COLOUR_POWERS: OnceLock<[usize; 3]> = OnceLock::new();
+
+/// Theme set at startup
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Sets the theme. Only the first call has any effect
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// Returns the theme in use
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// Sets the powers at which the blue, yellow and red colours start to apply. Only the first
+/// call has any effect
+pub fn set_colour_powers(powers: [usize; 3]) {
+    let _ = COLOUR_POWERS.set(powers);
+}
+
+/// Returns the powers at which the blue, yellow and red colours start to apply
+pub fn colour_powers() -> [usize; 3] {
+    *COLOUR_POWERS.get().unwrap_or(&DEFAULT_COLOUR_POWERS)
+}
+
+/// Parses a power suffix (k, M, G, T, P or E)
+pub fn parse_power(power: &str) -> Option<usize> {
+    POWERS
+        .iter()
+        .skip(1)
+        .position(|p| p.eq_ignore_ascii_case(power))
+        .map(|i| i + 1)
+}
+
+/// Returns the colour for a value of the given power
+fn power_colour(power: usize) -> Color {
+    theme().colours[colour_powers().iter().filter(|&&p| p <= power).count()]
+}
+
+/// Units used to display memory quantities
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemUnits {
+    #[default]
+    Binary, // Powers of 1024
+    Decimal, // Powers of 1000
+    Bytes,   // Exact byte counts
+}
+
+impl MemUnits {
+    /// Returns the other unit system
+    pub fn toggle(self) -> Self {
+        match self {
+            MemUnits::Binary => MemUnits::Decimal,
+            MemUnits::Decimal => MemUnits::Binary,
+            MemUnits::Bytes => MemUnits::Bytes,
+        }
+    }
+}
+
+pub fn format_mem_qty(bytes: usize, units: MemUnits) -> Span<'static> {
+    let (base, suffixes) = match units {
+        MemUnits::Binary | MemUnits::Bytes => (1024_f64, BINARY_UNITS),
+        MemUnits::Decimal => (1000_f64, DECIMAL_UNITS),
+    };
+
+    // Unlimited values are read as the maximum
+    if bytes == usize::MAX {
+        let width = match units {
+            MemUnits::Bytes => 15,
+            _ => 5,
+        };
+
+        return Span::styled(
+            format!("{:>2$} {:3$}", "∞", "", width, suffixes[0].len()),
+            Style::default().fg(power_colour(0)),
+        );
+    }
+
     let mut fbytes = bytes as f64;
     let mut power = 0;
 
-    while power < 6 && fbytes >= 1024_f64 {
+    while power < 6 && fbytes >= base {
         power += 1;
-        fbytes /= 1024_f64;
+        fbytes /= base;
     }
 
-    let style = Style::default().fg(COLOURS[power]);
+    let style = Style::default().fg(power_colour(power));
+
+    // Keep the magnitude colouring for exact byte counts
+    if units == MemUnits::Bytes {
+        return Span::styled(
+            format!("{:>15} {}", format_thousands(bytes), suffixes[0]),
+            style,
+        );
+    }
 
     let dp = if power > 1 {
         let digits = successors(Some(fbytes), |&n| (n >= 10_f64).then_some(n / 10_f64)).count();
@@ -32,7 +129,145 @@ pub fn format_mem_qty(bytes: usize) -> Span<'static> {
         0
     };
 
-    Span::styled(format!("{:>5.*} {}", dp, fbytes, POWERS[power]), style)
+    Span::styled(format!("{:>5.*} {}", dp, fbytes, suffixes[power]), style)
+}
+
+/// Formats a rate in bytes per second
+pub fn format_rate(bytes_per_sec: usize, units: MemUnits) -> Span<'static> {
+    let span = format_mem_qty(bytes_per_sec, units);
+
+    Span::styled(format!("{}/s", span.content.trim()), span.style)
+}
+
+/// Formats an integer with thousands separators
+pub fn format_thousands(value: usize) -> String {
+    let digits = value.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+/// Decodes systemd style `\xNN` escapes in a unit name. Invalid escapes are left as they are
+/// and bytes which aren't valid UTF-8 are replaced
+pub fn unescape_systemd(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && bytes.get(i + 1) == Some(&b'x') {
+            if let Some(value) = name
+                .get(i + 2..i + 4)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                result.push(value);
+                i += 4;
+                continue;
+            }
+        }
+
+        result.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+/// Quotes a CSV field if required
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Quotes and escapes a JSON string
+pub fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+
+    result.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+
+    result
+}
+
+/// Builds an export file name in the current directory with a timestamp
+pub fn export_file_name(desc: &str, ext: &str) -> PathBuf {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    PathBuf::from(format!("cgroup_mem-{}-{}.{}", desc, secs, ext))
+}
+
+/// Formats a duration in microseconds
+pub fn format_duration(usecs: usize) -> Span<'static> {
+    const UNITS: [(&str, f64); 6] = [
+        ("us", 1000_f64),
+        ("ms", 1000_f64),
+        ("s", 60_f64),
+        ("m", 60_f64),
+        ("h", 24_f64),
+        ("d", f64::MAX),
+    ];
+
+    let mut ftime = usecs as f64;
+    let mut unit = 0;
+
+    while unit < UNITS.len() - 1 && ftime >= UNITS[unit].1 {
+        ftime /= UNITS[unit].1;
+        unit += 1;
+    }
+
+    let style = Style::default().fg(power_colour(unit));
+
+    let dp = if unit > 0 {
+        let digits = successors(Some(ftime), |&n| (n >= 10_f64).then_some(n / 10_f64)).count();
+        3_usize.saturating_sub(digits)
+    } else {
+        0
+    };
+
+    Span::styled(format!("{:>5.*}{:>2}", dp, ftime, UNITS[unit].0), style)
+}
+
+/// Formats a percentage given in hundredths
+pub fn format_pct(hundredths: usize) -> Span<'static> {
+    let colours = &theme().colours;
+
+    let colour = match hundredths {
+        0..=999 => colours[0],
+        1000..=4999 => colours[2],
+        _ => colours[3],
+    };
+
+    Span::styled(
+        format!("{:>6.2}%", hundredths as f64 / 100_f64),
+        Style::default().fg(colour),
+    )
 }
 
 pub fn format_qty(qty: usize) -> Span<'static> {
@@ -44,7 +279,7 @@ pub fn format_qty(qty: usize) -> Span<'static> {
         fqty /= 1000_f64;
     }
 
-    let style = Style::default().fg(COLOURS[power]);
+    let style = Style::default().fg(power_colour(power));
 
     let dp = if power > 0 {
         let digits = successors(Some(fqty), |&n| (n >= 10_f64).then_some(n / 10_f64)).count();
@@ -55,3 +290,35 @@ pub fn format_qty(qty: usize) -> Span<'static> {
 
     Span::styled(format!("{:>4.*} {}", dp, fqty, POWERS[power]), style)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescape_systemd_escapes() {
+        assert_eq!(unescape_systemd(r"foo\x2dbar.service"), "foo-bar.service");
+        assert_eq!(unescape_systemd(r"\x2fa\x20b"), "/a b");
+        assert_eq!(unescape_systemd("plain.slice"), "plain.slice");
+    }
+
+    #[test]
+    fn unescape_systemd_invalid_hex() {
+        assert_eq!(unescape_systemd(r"foo\xzzbar"), r"foo\xzzbar");
+        assert_eq!(unescape_systemd(r"foo\x+fbar"), r"foo\x+fbar");
+        assert_eq!(unescape_systemd(r"foo\y2d"), r"foo\y2d");
+    }
+
+    #[test]
+    fn unescape_systemd_truncated() {
+        assert_eq!(unescape_systemd(r"foo\x"), r"foo\x");
+        assert_eq!(unescape_systemd(r"foo\x2"), r"foo\x2");
+        assert_eq!(unescape_systemd("foo\\"), "foo\\");
+    }
+
+    #[test]
+    fn unescape_systemd_invalid_utf8() {
+        assert_eq!(unescape_systemd(r"a\xffb"), "a\u{fffd}b");
+        assert_eq!(unescape_systemd(r"\xc3\xa9"), "\u{e9}");
+    }
+}
@@ -3,13 +3,19 @@
 //! CGroup memory statistics display
 
 mod app;
+mod backend;
 mod cgroup;
+mod clipboard;
+mod config;
 mod file_proc;
 mod formatters;
 mod proc;
 
 use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
 
+use clap::builder::{PossibleValuesParser, TypedValueParser};
 use clap::Parser;
 use crossterm::cursor::MoveTo;
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
@@ -25,9 +31,20 @@ use crossterm::terminal::{
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 
-use crate::app::App;
-use crate::cgroup::get_cgroup2_mount_point;
-use crate::cgroup::stats::STATS;
+use crate::app::keymap::KeyMap;
+use crate::app::{App, AppOptions};
+use crate::backend::TermBackend;
+use crate::cgroup::stats::{build_stats, find_cgroup_stat, find_stat, Stat, STATS};
+use crate::cgroup::{
+    get_cgroup2_mount_point,
+    load_cgroups,
+    render_tree_text,
+    CGroup,
+    CGroupSortOrder,
+    LoadOptions,
+};
+use crate::config::{default_config_path, default_theme_path, Config, State, Theme};
+use crate::formatters::{parse_power, set_colour_powers, set_theme, MemUnits};
 
 /// Command line arguments
 #[derive(Parser, Debug)]
@@ -41,22 +58,160 @@ struct Args {
     #[clap(short = 'l', long = "list", action)]
     list_stats: bool,
 
-    /// Initial statistic to display
-    #[clap(short = 's', long = "stat", default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..=(STATS.len() as i64)))]
-    stat: u16,
+    /// Initial statistic to display [default: last used or 1]
+    #[clap(short = 's', long = "stat", value_parser = clap::value_parser!(u16).range(1..=(STATS.len() as i64)))]
+    stat: Option<u16>,
+
+    /// Only show the cgroup subtree at this path relative to the cgroup2 mount point
+    #[clap(long = "cgroup")]
+    cgroup: Option<PathBuf>,
+
+    /// Initial cgroup sort order [default: last used or stat-desc]
+    #[clap(long = "sort", value_parser = PossibleValuesParser::new(["name-asc", "name-desc", "stat-asc", "stat-desc"]).map(|s| CGroupSortOrder::from_name(&s).unwrap()))]
+    sort: Option<CGroupSortOrder>,
+
+    /// Print the cgroup tree to stdout once and exit
+    #[clap(long = "once", action)]
+    once: bool,
+
+    /// Print raw "depth value /path" lines with --once for scripting
+    #[clap(long = "numeric", action, requires = "once")]
+    numeric: bool,
+
+    /// Only offer statistics for these controllers (comma separated, e.g. memory,cpu)
+    #[clap(long = "only", value_delimiter = ',')]
+    only: Vec<String>,
+
+    /// Maximum number of processes to show in the process table
+    #[clap(long = "proc-limit", value_parser = clap::value_parser!(u32).range(1..))]
+    proc_limit: Option<u32>,
+
+    /// Number of cgroups to show in the memory and swap footprint dashboard
+    #[clap(long = "top", default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    top: u32,
+
+    /// Refresh interval in seconds
+    #[clap(short = 'i', long = "interval", default_value_t = 5.0, value_parser = parse_interval)]
+    interval: f64,
+
+    /// Highlight cgroups where a memory statistic exceeds this size (e.g. 512M, 1G)
+    #[clap(long = "warn", value_parser = parse_size)]
+    warn: Option<usize>,
+
+    /// Directory to use as the cgroup2 mount point instead of finding it in /proc/mounts.
+    /// Processes are not looked up unless --proc-root is also given
+    #[clap(long = "root")]
+    fs_root: Option<PathBuf>,
+
+    /// Directory the proc filesystem is mounted on [default: /proc]
+    #[clap(long = "proc-root")]
+    proc_root: Option<PathBuf>,
+
+    /// Label to show in window titles
+    #[clap(long = "label")]
+    label: Option<String>,
+
+    /// Configuration file to use
+    #[clap(long = "config")]
+    config: Option<PathBuf>,
+
+    /// Theme file to use
+    #[clap(long = "theme")]
+    theme: Option<PathBuf>,
+
+    /// Print a summary of the largest cgroup when exiting
+    #[clap(long = "summary-on-exit", action)]
+    summary_on_exit: bool,
+
+    /// Don't load or save the statistic and sort orders between runs
+    #[clap(long = "no-persist", action)]
+    no_persist: bool,
+
+    /// Don't use colours. Also disabled by setting the NO_COLOR environment variable
+    #[clap(long = "no-color", action)]
+    no_color: bool,
 }
 
 fn main() -> Result<(), io::Error> {
     // Parse command line arguments
     let args = Args::parse();
 
+    // Load the configuration file
+    let config = load_config(&args.config);
+
+    // Apply the configured colour thresholds
+    if let Some(powers) = config.colour_powers() {
+        set_colour_powers(powers);
+    }
+
+    // Load the theme file
+    set_theme(load_theme(&args.theme));
+
+    // Build the statistic list in the preferred order
+    let mut stats = build_stat_list(&config);
+
+    // Restrict to the requested controllers. Core cgroup statistics are always offered
+    if !args.only.is_empty() {
+        stats.retain(|s| {
+            s.controller()
+                .is_none_or(|c| args.only.iter().any(|o| o == c))
+        });
+
+        if !stats.iter().any(|s| s.controller().is_some()) {
+            eprintln!(
+                "No statistics available for controllers: {}",
+                args.only.join(", ")
+            );
+            std::process::exit(1);
+        }
+    }
+
+    // Load the state from the last run. A persisted statistic no longer available is ignored
+    let mut state = if args.no_persist {
+        State::default()
+    } else {
+        State::load()
+    };
+
+    if let Some(stat) = args.stat {
+        if stat as usize > stats.len() {
+            eprintln!("Statistic number must be at most {}", stats.len());
+            std::process::exit(1);
+        }
+
+        if stats[stat as usize - 1].proc_only() {
+            eprintln!("Statistic {} is only available in the process view", stat);
+            std::process::exit(1);
+        }
+
+        state.stat = Some(stats[stat as usize - 1].short_desc().to_string());
+    }
+
+    if let Some(sort) = args.sort {
+        state.cgroup_sort = Some(sort);
+    }
+
     if args.list_stats {
-        list_stats();
+        list_stats(&stats);
         return Ok(());
     }
 
+    for dir in [&args.fs_root, &args.proc_root].into_iter().flatten() {
+        if !dir.is_dir() {
+            eprintln!("{} is not a directory", dir.display());
+            std::process::exit(1);
+        }
+    }
+
+    // Processes are only looked up in /proc if the cgroup2 mount point is the real one
+    let proc_root = match (args.proc_root, &args.fs_root) {
+        (Some(proc_root), _) => Some(proc_root),
+        (None, None) => Some(PathBuf::from("/proc")),
+        (None, Some(_)) => None,
+    };
+
     // Try and find path to the cgroup2 mount in /proc/mounts
-    let cgroup2fs = match get_cgroup2_mount_point() {
+    let cgroup2fs = match args.fs_root.clone().or_else(get_cgroup2_mount_point) {
         Some(path) => path,
         None => {
             eprintln!("Unable to find the mount point for the cgroup2 file system");
@@ -64,22 +219,72 @@ fn main() -> Result<(), io::Error> {
         }
     };
 
+    // Check the subtree to show exists
+    let root = match &args.cgroup {
+        Some(cgroup) => match subtree_root(&cgroup2fs, cgroup) {
+            Some(root) => root,
+            None => {
+                eprintln!(
+                    "CGroup {} not found under {}",
+                    cgroup.display(),
+                    cgroup2fs.display()
+                );
+                std::process::exit(1);
+            }
+        },
+        None => PathBuf::new(),
+    };
+
+    // Print the tree without starting the user interface if required
+    if args.once {
+        print_tree(
+            &cgroup2fs,
+            &root,
+            &stats[find_cgroup_stat(&stats, state.stat.as_deref())],
+            state.cgroup_sort.unwrap_or(CGroupSortOrder::StatDsc),
+            args.numeric,
+        );
+        return Ok(());
+    }
+
+    // Build the application options
+    let options = AppOptions {
+        keymap: build_keymap(&config),
+        label: args.label,
+        root,
+        proc_limit: args.proc_limit.map(|l| l as usize),
+        footprint_top: args.top as usize,
+        warn: args.warn,
+        proc_root,
+        interval: Duration::from_secs_f64(args.interval),
+        debug: args.debug,
+    };
+
     // Set up terminal
-    match setup_terminal() {
+    // Disable colours if requested on the command line or by a non-empty NO_COLOR
+    let colour = !args.no_color && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty());
+
+    match setup_terminal(colour) {
         Ok(mut terminal) => {
             // Run the application
-            let mut app = App::new(
-                &mut terminal,
-                &cgroup2fs,
-                (args.stat - 1) as usize,
-                args.debug,
-            );
+            let mut app = App::new(&mut terminal, &cgroup2fs, &stats, state, &options);
 
             let res = app.run();
 
+            // Save the state for the next run
+            if !args.no_persist {
+                let _ = app.state().save();
+            }
+
+            let summary = app.summary().filter(|_| args.summary_on_exit);
+
             // Restore terminal
             restore_terminal(Some(&mut terminal))?;
 
+            if let Some(summary) = summary {
+                println!("{}", summary);
+            }
+
             res
         }
         Err(e) => {
@@ -89,9 +294,9 @@ fn main() -> Result<(), io::Error> {
     }
 }
 
-type TermType = Terminal<CrosstermBackend<io::Stdout>>;
+type TermType = Terminal<TermBackend<CrosstermBackend<io::Stdout>>>;
 
-fn setup_terminal() -> Result<TermType, io::Error> {
+fn setup_terminal(colour: bool) -> Result<TermType, io::Error> {
     enable_raw_mode()?;
 
     let mut stdout = io::stdout();
@@ -103,7 +308,7 @@ fn setup_terminal() -> Result<TermType, io::Error> {
         Clear(ClearType::All)
     )?;
 
-    let backend = CrosstermBackend::new(stdout);
+    let backend = TermBackend::new(CrosstermBackend::new(stdout), colour);
     let terminal = Terminal::new(backend)?;
 
     Ok(terminal)
@@ -127,10 +332,225 @@ fn restore_terminal(terminal: Option<&mut TermType>) -> Result<(), io::Error> {
     Ok(())
 }
 
-fn list_stats() {
+fn parse_interval(arg: &str) -> Result<f64, String> {
+    let secs: f64 = arg
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", arg))?;
+
+    if secs > 0_f64 && Duration::try_from_secs_f64(secs).is_ok() {
+        Ok(secs)
+    } else {
+        Err("interval must be greater than zero".into())
+    }
+}
+
+fn parse_size(arg: &str) -> Result<usize, String> {
+    // Split off any k, M, G or T suffix, which are powers of 1024
+    let (number, power) = match arg.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => match parse_power(&arg[i..]) {
+            Some(power) if power <= 4 => (&arg[..i], power),
+            _ => return Err(format!("'{}' is not a valid suffix (k, M, G or T)", c)),
+        },
+        _ => (arg, 0),
+    };
+
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", number))?;
+
+    if !value.is_finite() {
+        return Err(format!("'{}' is not a finite number", number));
+    }
+
+    if value < 0_f64 {
+        return Err("size must not be negative".into());
+    }
+
+    let size = value * 1024_f64.powi(power as i32);
+
+    // usize::MAX rounds up to 2^64 as a float, so anything at least that large doesn't fit
+    if size >= usize::MAX as f64 {
+        return Err(format!("'{}' is too large", arg));
+    }
+
+    Ok(size as usize)
+}
+
+fn load_config(path: &Option<PathBuf>) -> Config {
+    let (path, explicit) = match path {
+        Some(path) => (path.clone(), true),
+        None => match default_config_path() {
+            Some(path) => (path, false),
+            None => return Config::default(),
+        },
+    };
+
+    if !explicit && !path.exists() {
+        return Config::default();
+    }
+
+    match Config::load(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!(
+                "Unable to load configuration file {}: {}",
+                path.display(),
+                e
+            );
+            Config::default()
+        }
+    }
+}
+
+fn load_theme(path: &Option<PathBuf>) -> Theme {
+    let path = match path {
+        Some(path) => path.clone(),
+        None => match default_theme_path() {
+            Some(path) if path.exists() => path,
+            _ => return Theme::default(),
+        },
+    };
+
+    match Theme::load(&path) {
+        Ok(theme) => theme,
+        Err(e) => {
+            eprintln!("Unable to load theme file {}: {}", path.display(), e);
+            Theme::default()
+        }
+    }
+}
+
+fn build_stat_list(config: &Config) -> Vec<Stat<'static>> {
+    let order: Vec<usize> = config
+        .stat_order()
+        .iter()
+        .filter_map(|name| {
+            let stat = find_stat(&STATS, name);
+
+            if stat.is_none() {
+                eprintln!("Unknown statistic '{}' in configured order", name);
+            }
+
+            stat
+        })
+        .collect();
+
+    build_stats(&order)
+}
+
+fn build_keymap(config: &Config) -> KeyMap {
+    let mut keymap = KeyMap::default();
+
+    for (name, key) in config.key_bindings() {
+        if !keymap.bind(name, *key) {
+            eprintln!("Unknown key binding action '{}' in configuration", name);
+        }
+    }
+
+    keymap
+}
+
+fn list_stats(stats: &[Stat]) {
     println!("Available statistics:");
 
-    for (i, s) in STATS.iter().enumerate() {
+    for (i, s) in stats.iter().enumerate() {
         println!("  {:>2}: {}", i + 1, s.desc());
     }
 }
+
+/// Converts a subtree path to be relative to the cgroup2 mount point. Returns None if the
+/// path is not a cgroup directory under the mount point
+fn subtree_root(cgroup2fs: &Path, cgroup: &Path) -> Option<PathBuf> {
+    let root = cgroup
+        .strip_prefix(cgroup2fs)
+        .unwrap_or(cgroup)
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(Some(part)),
+            Component::RootDir | Component::CurDir => None,
+            _ => Some(None),
+        })
+        .collect::<Option<PathBuf>>()?;
+
+    cgroup2fs.join(&root).is_dir().then_some(root)
+}
+
+fn print_tree(cgroup2fs: &Path, root: &Path, stat: &Stat, sort: CGroupSortOrder, numeric: bool) {
+    let cgroups = match load_cgroups(cgroup2fs, root, stat, sort, LoadOptions::default()) {
+        Ok(cgroups) => cgroups,
+        Err(e) => {
+            eprintln!("Unable to load cgroups: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if numeric {
+        print!("{}", render_tree_text(&cgroups));
+        return;
+    }
+
+    println!("{}:", stat.short_desc());
+    print_tree_level(&cgroups, stat, 0);
+}
+
+fn print_tree_level(cgroups: &[CGroup], stat: &Stat, depth: usize) {
+    for cg in cgroups {
+        let name = match cg.path().file_name() {
+            Some(name) => name.to_string_lossy(),
+            None => "/".into(),
+        };
+
+        let indent = "  ".repeat(depth);
+
+        match cg.error() {
+            Some(msg) => println!("{}{:>9} {} ({})", indent, "<Error>", name, msg),
+            None => println!(
+                "{}{} {}",
+                indent,
+                stat.format().format(cg.stat(), MemUnits::default()).content,
+                name
+            ),
+        }
+
+        print_tree_level(cg.children(), stat, depth + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_parsed() {
+        assert_eq!(parse_size("0"), Ok(0));
+        assert_eq!(parse_size("100"), Ok(100));
+        assert_eq!(parse_size("1k"), Ok(1024));
+        assert_eq!(parse_size("1.5K"), Ok(1536));
+        assert_eq!(parse_size("2M"), Ok(2 << 20));
+        assert_eq!(parse_size("0.5G"), Ok(1 << 29));
+        assert_eq!(parse_size("3T"), Ok(3 << 40));
+        assert_eq!(parse_size("16000000T"), Ok(16_000_000 << 40));
+    }
+
+    #[test]
+    fn invalid_sizes_rejected() {
+        for arg in [
+            "",
+            "k",
+            "abc",
+            "1P",
+            "1x",
+            "-1",
+            "-1k",
+            "nan",
+            "infk",
+            "nank",
+            "1e400",
+            "1e30",
+            "16777216T",
+            "18446744073709551616",
+        ] {
+            assert!(parse_size(arg).is_err(), "{:?} accepted", arg);
+        }
+    }
+}
@@ -1,8 +1,10 @@
-use std::fs::File;
+use std::collections::HashMap;
+use std::fs::{self, File};
 use std::io::{self, BufRead, BufReader};
-use std::path::{Path, PathBuf};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
 
-use crate::cgroup::stats::{ProcStatType, STATS};
+use crate::cgroup::stats::{ProcStatType, Stat};
 use crate::file_proc::{
     get_file_processor,
     FileProcessor,
@@ -12,11 +14,16 @@ use crate::file_proc::{
 
 pub struct Proc {
     pub pid: usize,
+    pub ppid: Option<usize>,
     pub cmd: String,
     pub stat: Result<usize, FileProcessorError>,
+    pub container: Option<String>,
+    pub uid: Option<usize>,
+    pub user: String,
+    pub state: Option<char>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProcSortOrder {
     PidAsc,
     PidDsc,
@@ -26,13 +33,117 @@ pub enum ProcSortOrder {
     CmdDsc,
 }
 
+impl ProcSortOrder {
+    /// Returns the name of the sort order used in the state file
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProcSortOrder::PidAsc => "pid-asc",
+            ProcSortOrder::PidDsc => "pid-desc",
+            ProcSortOrder::StatAsc => "stat-asc",
+            ProcSortOrder::StatDsc => "stat-desc",
+            ProcSortOrder::CmdAsc => "cmd-asc",
+            ProcSortOrder::CmdDsc => "cmd-desc",
+        }
+    }
+
+    /// Finds a sort order by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pid-asc" => Some(ProcSortOrder::PidAsc),
+            "pid-desc" => Some(ProcSortOrder::PidDsc),
+            "stat-asc" => Some(ProcSortOrder::StatAsc),
+            "stat-desc" => Some(ProcSortOrder::StatDsc),
+            "cmd-asc" => Some(ProcSortOrder::CmdAsc),
+            "cmd-desc" => Some(ProcSortOrder::CmdDsc),
+            _ => None,
+        }
+    }
+}
+
+/// Process states to show
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProcStateFilter {
+    #[default]
+    All,
+    Running,
+    Sleeping,
+    DiskSleep,
+    Stopped,
+    Zombie,
+}
+
+impl ProcStateFilter {
+    /// Returns the next filter in the cycle
+    pub fn next(&self) -> Self {
+        match self {
+            ProcStateFilter::All => ProcStateFilter::Running,
+            ProcStateFilter::Running => ProcStateFilter::Sleeping,
+            ProcStateFilter::Sleeping => ProcStateFilter::DiskSleep,
+            ProcStateFilter::DiskSleep => ProcStateFilter::Stopped,
+            ProcStateFilter::Stopped => ProcStateFilter::Zombie,
+            ProcStateFilter::Zombie => ProcStateFilter::All,
+        }
+    }
+
+    /// Returns the description of the filter
+    pub fn desc(&self) -> &'static str {
+        match self {
+            ProcStateFilter::All => "all",
+            ProcStateFilter::Running => "running",
+            ProcStateFilter::Sleeping => "sleeping",
+            ProcStateFilter::DiskSleep => "disk sleep",
+            ProcStateFilter::Stopped => "stopped",
+            ProcStateFilter::Zombie => "zombie",
+        }
+    }
+
+    /// Tests if a process state passes the filter
+    pub fn matches(&self, state: Option<char>) -> bool {
+        match self {
+            ProcStateFilter::All => true,
+            ProcStateFilter::Running => state == Some('R'),
+            ProcStateFilter::Sleeping => matches!(state, Some('S' | 'I')),
+            ProcStateFilter::DiskSleep => state == Some('D'),
+            ProcStateFilter::Stopped => matches!(state, Some('T' | 't')),
+            ProcStateFilter::Zombie => state == Some('Z'),
+        }
+    }
+}
+
+/// Identifies the program a process is running. It changes if the PID is reused or the process
+/// execs, as the start time or the command name and argument addresses differ
+#[derive(Clone, PartialEq, Eq)]
+struct ProcImage {
+    start_time: u64,
+    comm: String,
+    args: (u64, u64),
+}
+
+/// Command lines of the processes from the last load
+#[derive(Default)]
+pub struct CmdlineCache {
+    cmdlines: HashMap<usize, (ProcImage, String)>,
+}
+
+/// Options controlling how process information is loaded
+#[derive(Clone, Copy, Default)]
+pub struct ProcLoadOptions<'a> {
+    pub prefer_comm: bool,
+    pub container: bool,
+    /// Directory the proc filesystem is mounted on. Only PIDs are loaded if None
+    pub proc_root: Option<&'a Path>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn load_procs(
     cgroup2fs: &Path,
     cgroup: &Path,
     include_children: bool,
     threads: bool,
-    stat: usize,
+    stat: &Stat,
     sort: ProcSortOrder,
+    options: ProcLoadOptions<'_>,
+    cache: &mut CmdlineCache,
 ) -> io::Result<Vec<Proc>> {
     // Get PID list
     let mut path = cgroup2fs.to_path_buf();
@@ -43,79 +154,313 @@ pub fn load_procs(
     // Create file processor for getting command line / comm
     let file_processor = SingleValueProcessor::default();
 
+    // Create the real UID processor and load the user names once for all processes
+    let uid_processor = get_file_processor("status/=/1/Uid:/2").unwrap();
+    let users = load_user_names();
+
     // Create the stats processor (if required)
-    let stat_processor = get_file_processor(STATS[stat].proc_def());
-    let stat_type = STATS[stat].proc_stat_type();
+    let stat_processor = get_file_processor(stat.proc_def());
+    let stat_type = stat.proc_stat_type();
+
+    // Take the cached command lines. Only entries for processes still present are put back
+    let mut old_cmdlines = std::mem::take(&mut cache.cmdlines);
 
     let mut procs: Vec<Proc> = pids
         .into_iter()
         .map(|pid| {
-            // Build /proc path
-            let proc_path = PathBuf::from(format!("/proc/{}", pid));
-
-            // Get command line
-            let cmd = match file_processor.get_value(&proc_path.join("cmdline")) {
-                Ok(string) => string
-                    .chars()
-                    .map(|c| if c == '\x00' { ' ' } else { c })
-                    .collect(),
-                Err(_) => match file_processor.get_value(&proc_path.join("comm")) {
-                    Ok(string) => format!("[{}]", string),
-                    Err(_) => "<Unknown>".into(),
-                },
+            // The PIDs may not refer to processes on this system
+            let Some(proc_root) = options.proc_root else {
+                return Proc {
+                    pid,
+                    ppid: None,
+                    cmd: "<Unknown>".into(),
+                    stat: Err(FileProcessorError::ValueNotFound),
+                    container: None,
+                    uid: None,
+                    user: "?".into(),
+                    state: None,
+                };
             };
 
-            // Get stat
-            let stat = if let Some(processor) = &stat_processor {
-                let mut value = processor.get_stat(&proc_path);
-
-                match stat_type {
-                    ProcStatType::MemQtyKb => {
-                        value = match value {
-                            Ok(value) => Ok(value * 1024),
-                            v => v,
-                        }
+            // Build /proc path
+            let proc_path = proc_root.join(pid.to_string());
+
+            // Get the process state, parent PID and program identity
+            let (state, ppid, image) = read_stat(&proc_path);
+
+            // Get command line, using the cached value if the process is running the same program
+            let mut cmdline = || {
+                let cmdline = match (old_cmdlines.remove(&pid), &image) {
+                    (Some((cached_image, cmdline)), Some(image)) if cached_image == *image => {
+                        Ok(cmdline)
                     }
-                    _ => panic!("Unexpected stat type"),
+                    _ => file_processor
+                        .get_value(&proc_path.join("cmdline"))
+                        .map(|string| {
+                            string
+                                .chars()
+                                .map(|c| if c == '\x00' { ' ' } else { c })
+                                .collect::<String>()
+                        }),
+                };
+
+                if let (Ok(cmdline), Some(image)) = (&cmdline, &image) {
+                    cache.cmdlines.insert(pid, (image.clone(), cmdline.clone()));
                 }
 
-                value
+                cmdline
+            };
+
+            let comm = || file_processor.get_value(&proc_path.join("comm"));
+
+            let cmd = if options.prefer_comm {
+                match comm() {
+                    Ok(string) => string,
+                    Err(_) => cmdline().unwrap_or_else(|_| "<Unknown>".into()),
+                }
+            } else {
+                match cmdline() {
+                    Ok(string) => string,
+                    Err(_) => match comm() {
+                        Ok(string) => format!("[{}]", string),
+                        Err(_) => "<Unknown>".into(),
+                    },
+                }
+            };
+
+            // Get stat
+            let stat = get_proc_stat(&proc_path, stat_processor.as_deref(), stat_type);
+
+            // Get container ID
+            let container = if options.container {
+                fs::read_to_string(proc_path.join("cgroup"))
+                    .ok()
+                    .and_then(|cgroups| {
+                        // The cgroup v2 entry is of the form "0::/path"
+                        cgroups
+                            .lines()
+                            .find_map(|line| line.strip_prefix("0::"))
+                            .and_then(container_id)
+                    })
             } else {
-                Ok(0)
+                None
+            };
+
+            // Get the owning user name, falling back to the UID
+            let uid = uid_processor.get_stat(&proc_path).ok();
+
+            let user = match uid {
+                Some(uid) => users.get(&uid).cloned().unwrap_or_else(|| uid.to_string()),
+                None => "?".into(),
             };
 
-            Proc { pid, cmd, stat }
+            Proc {
+                pid,
+                ppid,
+                cmd,
+                stat,
+                container,
+                uid,
+                user,
+                state,
+            }
         })
         .collect();
 
-    // Sort the processes
+    // Sort the processes, breaking ties by PID so the order is the same between loads
+    let stat_value = |p: &Proc| *p.stat.as_ref().unwrap_or(&0);
+
     match sort {
-        ProcSortOrder::PidAsc => procs.sort_by(|a, b| a.pid.cmp(&b.pid)),
+        ProcSortOrder::PidAsc => procs.sort_by_key(|p| p.pid),
         ProcSortOrder::PidDsc => procs.sort_by(|a, b| a.pid.cmp(&b.pid).reverse()),
-        ProcSortOrder::CmdAsc => procs.sort_by(|a, b| a.cmd.cmp(&b.cmd)),
-        ProcSortOrder::CmdDsc => procs.sort_by(|a, b| a.cmd.cmp(&b.cmd).reverse()),
-        ProcSortOrder::StatAsc => {
-            procs.sort_by(|a, b| {
-                a.stat
-                    .as_ref()
-                    .unwrap_or(&0)
-                    .cmp(b.stat.as_ref().unwrap_or(&0))
-            });
+        ProcSortOrder::CmdAsc => procs.sort_by(|a, b| a.cmd.cmp(&b.cmd).then(a.pid.cmp(&b.pid))),
+        ProcSortOrder::CmdDsc => {
+            procs.sort_by(|a, b| a.cmd.cmp(&b.cmd).reverse().then(a.pid.cmp(&b.pid)))
         }
-        ProcSortOrder::StatDsc => {
-            procs.sort_by(|a, b| {
-                a.stat
-                    .as_ref()
-                    .unwrap_or(&0)
-                    .cmp(b.stat.as_ref().unwrap_or(&0))
-                    .reverse()
-            });
+        ProcSortOrder::StatAsc => {
+            procs.sort_by(|a, b| stat_value(a).cmp(&stat_value(b)).then(a.pid.cmp(&b.pid)))
         }
+        ProcSortOrder::StatDsc => procs.sort_by(|a, b| {
+            stat_value(a)
+                .cmp(&stat_value(b))
+                .reverse()
+                .then(a.pid.cmp(&b.pid))
+        }),
     }
 
     Ok(procs)
 }
 
+/// Reads the process state character, parent PID and program identity from /proc/<pid>/stat
+fn read_stat(proc_path: &Path) -> (Option<char>, Option<usize>, Option<ProcImage>) {
+    let Ok(stat) = fs::read_to_string(proc_path.join("stat")) else {
+        return (None, None, None);
+    };
+
+    // The fields follow the parenthesised command name, which may itself contain parentheses
+    let Some((head, rest)) = stat.rsplit_once(')') else {
+        return (None, None, None);
+    };
+
+    let comm = head.split_once('(').map_or("", |(_, comm)| comm);
+
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |i: usize| fields.get(i).and_then(|field| field.parse::<u64>().ok());
+
+    // The state is the first field, the parent PID the second, the start time the twentieth
+    // and the argument start and end addresses the forty-sixth and forty-seventh. The
+    // addresses read as zero without permission to trace the process
+    let state = fields.first().and_then(|field| field.chars().next());
+    let ppid = fields.get(1).and_then(|field| field.parse().ok());
+
+    let image = field(19).map(|start_time| ProcImage {
+        start_time,
+        comm: comm.to_string(),
+        args: (field(45).unwrap_or(0), field(46).unwrap_or(0)),
+    });
+
+    (state, ppid, image)
+}
+
+/// Loads the user names by UID from /etc/passwd
+fn load_user_names() -> HashMap<usize, String> {
+    let Ok(contents) = fs::read_to_string("/etc/passwd") else {
+        return HashMap::new();
+    };
+
+    // Lines are of the form "name:password:uid:gid:..."
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+/// Length of the abbreviated container ID
+const CONTAINER_ID_LEN: usize = 12;
+
+/// Extracts a short container ID from a cgroup path. Container runtimes name the cgroup
+/// after the 64 hex digit container ID, optionally with a runtime prefix and a .scope
+/// suffix (e.g. docker-<id>.scope or cri-containerd-<id>.scope)
+pub fn container_id(cgroup: &str) -> Option<String> {
+    cgroup.rsplit('/').find_map(|segment| {
+        let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+        let id = segment.rsplit('-').next().unwrap_or(segment);
+
+        if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            Some(id[..CONTAINER_ID_LEN].to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Reloads the statistic for a single process. Returns None if the process no longer exists
+pub fn reload_proc_stat(
+    proc_root: &Path,
+    pid: usize,
+    stat: &Stat,
+) -> Option<Result<usize, FileProcessorError>> {
+    let proc_path = proc_root.join(pid.to_string());
+
+    if !proc_path.exists() {
+        return None;
+    }
+
+    let stat_processor = get_file_processor(stat.proc_def());
+
+    Some(get_proc_stat(
+        &proc_path,
+        stat_processor.as_deref(),
+        stat.proc_stat_type(),
+    ))
+}
+
+/// Tests if a proc file system root is this process's own /proc, so its PIDs are those seen by
+/// kill. It must be the same mount as /proc and its self link must name this process
+pub fn own_proc_root(proc_root: &Path) -> bool {
+    let same_mount = match (proc_root.metadata(), Path::new("/proc").metadata()) {
+        (Ok(root), Ok(proc)) => root.dev() == proc.dev(),
+        _ => false,
+    };
+
+    same_mount
+        && fs::read_link(proc_root.join("self"))
+            .is_ok_and(|pid| pid.to_str() == Some(&std::process::id().to_string()))
+}
+
+/// Sends a signal to a process
+pub fn send_signal(pid: usize, signal: libc::c_int) -> io::Result<()> {
+    let pid = libc::pid_t::try_from(pid)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Invalid PID"))?;
+
+    // SAFETY: kill has no memory safety requirements
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Loads the memory totals from <proc root>/<pid>/smaps_rollup. Values are in bytes
+pub fn load_smaps_rollup(proc_root: &Path, pid: usize) -> io::Result<Vec<(String, usize)>> {
+    let file = File::open(proc_root.join(pid.to_string()).join("smaps_rollup"))?;
+
+    let mut values = Vec::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let columns: Vec<&str> = line.split_whitespace().collect();
+
+        // Value lines are of the form "Pss:   1234 kB"
+        if let [key, value, "kB"] = columns[..] {
+            if let (Some(key), Ok(value)) = (key.strip_suffix(':'), value.parse::<usize>()) {
+                values.push((key.to_string(), value * 1024));
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// Loads the fields of <proc root>/<pid>/status by name
+pub fn load_status(proc_root: &Path, pid: usize) -> io::Result<Vec<(String, String)>> {
+    let file = File::open(proc_root.join(pid.to_string()).join("status"))?;
+
+    let mut values = Vec::new();
+
+    for line in BufReader::new(file).lines() {
+        // Lines are of the form "VmRSS:    1234 kB"
+        if let Some((key, value)) = line?.split_once(':') {
+            values.push((key.to_string(), value.trim().to_string()));
+        }
+    }
+
+    Ok(values)
+}
+
+fn get_proc_stat(
+    proc_path: &Path,
+    processor: Option<&dyn FileProcessor>,
+    stat_type: ProcStatType,
+) -> Result<usize, FileProcessorError> {
+    if let Some(processor) = processor {
+        let value = processor.get_stat(proc_path);
+
+        match stat_type {
+            ProcStatType::MemQtyKb => value.map(|value| value * 1024),
+            _ => panic!("Unexpected stat type"),
+        }
+    } else {
+        Ok(0)
+    }
+}
+
 fn load_pids(cgroup_path: &Path, threads: bool, include_children: bool) -> io::Result<Vec<usize>> {
     let mut path = cgroup_path.to_path_buf();
 
@@ -157,3 +502,103 @@ fn load_pids(cgroup_path: &Path, threads: bool, include_children: bool) -> io::R
 
     Ok(pids)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cgroup::fixture::Fixture;
+    use crate::cgroup::stats::build_stats;
+
+    #[test]
+    fn own_proc_root_detected() {
+        assert!(own_proc_root(Path::new("/proc")));
+    }
+
+    #[test]
+    fn other_proc_root_rejected() {
+        let fixture = Fixture::new(&[("1/stat", "1 (init) S 0")]);
+
+        std::os::unix::fs::symlink(std::process::id().to_string(), fixture.path().join("self"))
+            .unwrap();
+
+        assert!(!own_proc_root(fixture.path()));
+        assert!(!own_proc_root(Path::new("/nonexistent")));
+    }
+
+    /// Builds the contents of a stat file with the given command name and argument addresses
+    fn stat_line(comm: &str, arg_start: u64) -> String {
+        let mut fields = vec!["0"; 47];
+        let arg_end = (arg_start + 100).to_string();
+        let arg_start = arg_start.to_string();
+
+        fields[0] = "S";
+        fields[19] = "500";
+        fields[45] = &arg_start;
+        fields[46] = &arg_end;
+
+        format!("100 ({comm}) {}", fields.join(" "))
+    }
+
+    #[test]
+    fn stat_identity_parsed() {
+        let fixture = Fixture::new(&[("100/stat", &stat_line("a (b) c", 1000))]);
+
+        let (state, ppid, image) = read_stat(&fixture.path().join("100"));
+        let image = image.unwrap();
+
+        assert_eq!(state, Some('S'));
+        assert_eq!(ppid, Some(0));
+        assert_eq!(image.start_time, 500);
+        assert_eq!(image.comm, "a (b) c");
+        assert_eq!(image.args.0, 1000);
+    }
+
+    #[test]
+    fn cmdline_cache_follows_exec_and_pruned() {
+        let fixture = Fixture::new(&[
+            ("cg/cgroup.procs", "100\n"),
+            ("proc/100/stat", &stat_line("sh", 1000)),
+            ("proc/100/cmdline", "sh\0-c\0"),
+        ]);
+
+        let stat = build_stats(&[])[0];
+        let proc_root = fixture.path().join("proc");
+        let options = ProcLoadOptions {
+            proc_root: Some(&proc_root),
+            ..Default::default()
+        };
+        let mut cache = CmdlineCache::default();
+
+        let load = |cache: &mut CmdlineCache| {
+            let path = fixture.path();
+            let procs = load_procs(
+                path,
+                Path::new("cg"),
+                false,
+                false,
+                &stat,
+                ProcSortOrder::PidAsc,
+                options,
+                cache,
+            )
+            .unwrap();
+
+            procs.into_iter().map(|p| p.cmd).collect::<Vec<_>>()
+        };
+
+        assert_eq!(load(&mut cache), ["sh -c "]);
+
+        // The same program keeps its cached command line
+        fs::write(proc_root.join("100/cmdline"), "changed\0").unwrap();
+        assert_eq!(load(&mut cache), ["sh -c "]);
+
+        // An exec changes the command name and argument addresses
+        fs::write(proc_root.join("100/stat"), stat_line("ls", 2000)).unwrap();
+        assert_eq!(load(&mut cache), ["changed "]);
+
+        // Processes which have gone are dropped from the cache
+        fs::write(fixture.path().join("cg/cgroup.procs"), "").unwrap();
+        assert!(load(&mut cache).is_empty());
+        assert!(cache.cmdlines.is_empty());
+    }
+}